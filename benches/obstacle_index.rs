@@ -0,0 +1,125 @@
+//! Compares obstacle queries through `ObstacleIndex` against a linear scan over
+//! every collider, for growing numbers of props scattered over a level0-sized
+//! ground.
+//!
+//! Run with `cargo bench --bench obstacle_index`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy_3d_fog_scene::collision::{ColliderShape, ObstacleBlocker, PlacedCollider};
+use bevy_3d_fog_scene::spatial::ObstacleIndex;
+use bevy_3d_fog_scene::WorldRng;
+
+const HALF_GROUND: f32 = 200.0;
+const QUERIES: usize = 2_000;
+
+fn main() {
+    println!(
+        "{:>8} {:>14} {:>14} {:>14} {:>14}",
+        "props", "scan LOS", "index LOS", "scan near", "index near"
+    );
+    for props in [10, 100, 1_000, 10_000] {
+        let mut rng = WorldRng::new(props as u64);
+        let colliders = scatter(&mut rng, props);
+        let mut index = ObstacleIndex::default();
+        for collider in &colliders {
+            index.insert(*collider);
+        }
+
+        // UGV-to-player sight lines and player-sized movement queries
+        let segments: Vec<(Vec3, Vec3)> = (0..QUERIES)
+            .map(|_| {
+                let from = random_point(&mut rng);
+                let offset = Vec3::new(rng.range(-80.0, 80.0), 0.0, rng.range(-80.0, 80.0));
+                (from, from + offset)
+            })
+            .collect();
+
+        let scan_los = time(|| {
+            for &(from, to) in &segments {
+                black_box(scan_line_of_sight(&colliders, from, to));
+            }
+        });
+        let index_los = time(|| {
+            for &(from, to) in &segments {
+                black_box(index.line_of_sight(from, to, None));
+            }
+        });
+        let scan_near = time(|| {
+            for &(from, _) in &segments {
+                black_box(scan_colliders_near(&colliders, from, 1.0));
+            }
+        });
+        let index_near = time(|| {
+            for &(from, _) in &segments {
+                black_box(index.colliders_near(from, 1.0));
+            }
+        });
+
+        println!(
+            "{:>8} {:>14} {:>14} {:>14} {:>14}",
+            props,
+            per_query(scan_los),
+            per_query(index_los),
+            per_query(scan_near),
+            per_query(index_near)
+        );
+    }
+}
+
+/// Boxes, cylinders and spheres of prop size with random placement and yaw
+fn scatter(rng: &mut WorldRng, count: usize) -> Vec<PlacedCollider> {
+    let mut world = World::new();
+    (0..count)
+        .map(|i| {
+            let shape = match i % 3 {
+                0 => ColliderShape::Box { half_size: Vec3::new(rng.range(0.5, 4.0), 2.0, rng.range(0.5, 4.0)) },
+                1 => ColliderShape::Cylinder { radius: rng.range(0.5, 4.0), half_height: 3.0 },
+                _ => ColliderShape::Sphere { radius: rng.range(0.5, 4.0) },
+            };
+            let transform = Transform::from_translation(random_point(rng)).with_rotation(Quat::from_rotation_y(rng.angle()));
+            PlacedCollider::new(world.spawn_empty().id(), &transform, &ObstacleBlocker::new(shape))
+        })
+        .collect()
+}
+
+fn random_point(rng: &mut WorldRng) -> Vec3 {
+    Vec3::new(rng.range(-HALF_GROUND, HALF_GROUND), 1.0, rng.range(-HALF_GROUND, HALF_GROUND))
+}
+
+fn scan_line_of_sight(colliders: &[PlacedCollider], from: Vec3, to: Vec3) -> bool {
+    let offset = to - from;
+    let distance = offset.length();
+    let dir = offset / distance;
+    !colliders.iter().any(|collider| collider.ray_cast(from, dir, distance).is_some())
+}
+
+fn scan_colliders_near(colliders: &[PlacedCollider], center: Vec3, radius: f32) -> Vec<PlacedCollider> {
+    colliders
+        .iter()
+        .filter(|collider| {
+            let (min, max) = collider.aabb();
+            center.xz().clamp(min.xz(), max.xz()).distance_squared(center.xz()) <= radius * radius
+        })
+        .copied()
+        .collect()
+}
+
+fn time(mut run: impl FnMut()) -> Duration {
+    // One warm-up pass, then the best of five
+    run();
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn per_query(total: Duration) -> String {
+    format!("{:.2} µs", total.as_secs_f64() * 1e6 / QUERIES as f64)
+}
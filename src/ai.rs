@@ -0,0 +1,499 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::archetype::Enemy;
+use crate::collision::Obstacles;
+use crate::nav::NavGrid;
+use crate::{ChasingSphere, CubeController, WorldRng};
+
+/// Seconds an alerted UGV holds still, facing what it saw, before reacting
+const ALERT_DURATION: f32 = 0.75;
+/// Seconds a UGV looks around the last known position before heading back
+const SEARCH_DURATION: f32 = 6.0;
+/// Distance at which a destination counts as reached
+const ARRIVE_DISTANCE: f32 = 1.0;
+/// Candidate points tried for each patrol waypoint before falling back to the center
+const PATROL_ATTEMPTS: usize = 8;
+
+/// What a UGV is currently doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Behavior {
+    /// Driving its patrol route
+    Patrol,
+    /// Stopped and facing something it just noticed
+    Alert,
+    /// Driving at the player, who is in sight
+    Chase,
+    /// Driving to the last known position and looking around there
+    Search,
+    /// Heading back to its patrol route after a fruitless search
+    Return,
+}
+
+/// Drive speed of each [`Behavior`], in units per second
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BehaviorSpeeds {
+    pub patrol: f32,
+    pub chase: f32,
+    pub search: f32,
+    pub return_to_patrol: f32,
+}
+
+impl BehaviorSpeeds {
+    /// Slower speeds for the calmer states, derived from the chase speed
+    pub fn from_chase_speed(chase: f32) -> Self {
+        Self { patrol: chase * 0.4, chase, search: chase * 0.7, return_to_patrol: chase * 0.5 }
+    }
+}
+
+/// Something a UGV noticed this frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stimulus {
+    /// The player is in plain sight at this position
+    Sight(Vec3),
+    /// A radar reported the player at this position
+    Alarm(Vec3),
+    /// Something made a noise at this position
+    Noise(Vec3),
+}
+
+/// Perception event addressed to one UGV's [`UgvBrain`]
+#[derive(Message, Debug, Clone, Copy)]
+pub struct Perceived {
+    pub ugv: Entity,
+    pub stimulus: Stimulus,
+}
+
+/// What a [`UgvBrain`] wants its body to do this frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Intent {
+    /// Drive to `target` at `speed`
+    MoveTo { target: Vec3, speed: f32 },
+    /// Stand still and turn towards a point
+    Face(Vec3),
+    /// Stand still and turn around on the spot
+    LookAround,
+    /// Do nothing
+    Idle,
+}
+
+/// Finite-state brain of a UGV.
+///
+/// Pure state with no ECS access: feed it stimuli with [`UgvBrain::perceive`],
+/// then call [`UgvBrain::tick`] once per frame with the UGV's position to advance
+/// the state machine and get back an [`Intent`]. Not being perceived during a
+/// frame counts as losing sight of the player.
+#[derive(Component, Debug, Clone)]
+pub struct UgvBrain {
+    behavior: Behavior,
+    speeds: BehaviorSpeeds,
+    patrol_route: Vec<Vec3>,
+    next_waypoint: usize,
+    last_known_position: Option<Vec3>,
+    /// Sighting received since the last tick
+    sighting: Option<Vec3>,
+    /// Reported position received since the last tick
+    report: Option<Vec3>,
+    /// Noise heard since the last tick
+    noise: Option<Vec3>,
+    time_in_behavior: f32,
+    search_time_left: f32,
+}
+
+impl UgvBrain {
+    pub fn new(patrol_route: Vec<Vec3>, speeds: BehaviorSpeeds) -> Self {
+        Self {
+            behavior: Behavior::Patrol,
+            speeds,
+            patrol_route,
+            next_waypoint: 0,
+            last_known_position: None,
+            sighting: None,
+            report: None,
+            noise: None,
+            time_in_behavior: 0.0,
+            search_time_left: SEARCH_DURATION,
+        }
+    }
+
+    pub fn behavior(&self) -> Behavior {
+        self.behavior
+    }
+
+    /// Where the player was last seen, until the UGV gives up on it
+    pub fn last_known_position(&self) -> Option<Vec3> {
+        self.last_known_position
+    }
+
+    pub fn perceive(&mut self, stimulus: Stimulus) {
+        match stimulus {
+            Stimulus::Sight(position) => self.sighting = Some(position),
+            Stimulus::Alarm(position) => self.report = Some(position),
+            Stimulus::Noise(position) => self.noise = Some(position),
+        }
+    }
+
+    /// Advances the state machine by `dt` seconds for a UGV at `position`
+    pub fn tick(&mut self, position: Vec3, dt: f32) -> Intent {
+        let sighting = self.sighting.take();
+        let report = self.report.take();
+        let noise = self.noise.take();
+        if sighting.is_some() {
+            self.last_known_position = sighting;
+        }
+        self.time_in_behavior += dt;
+
+        // Without a sighting, a radar report sends an idle or searching UGV straight to
+        // investigate, while a noise first makes it stop and turn towards the sound
+        match (sighting, report, noise) {
+            (None, Some(reported), _)
+                if matches!(self.behavior, Behavior::Patrol | Behavior::Search | Behavior::Return) =>
+            {
+                self.last_known_position = Some(reported);
+                self.enter(Behavior::Search);
+            }
+            (None, None, Some(heard)) => match self.behavior {
+                Behavior::Patrol | Behavior::Return => {
+                    self.last_known_position = Some(heard);
+                    self.enter(Behavior::Alert);
+                }
+                Behavior::Alert => self.last_known_position = Some(heard),
+                Behavior::Search => {
+                    self.last_known_position = Some(heard);
+                    self.enter(Behavior::Search);
+                }
+                Behavior::Chase => {}
+            },
+            _ => {}
+        }
+
+        match self.behavior {
+            Behavior::Patrol | Behavior::Return if sighting.is_some() => self.enter(Behavior::Alert),
+            Behavior::Alert if self.time_in_behavior >= ALERT_DURATION => {
+                self.enter(if sighting.is_some() { Behavior::Chase } else { Behavior::Search });
+            }
+            Behavior::Chase if sighting.is_none() => self.enter(Behavior::Search),
+            Behavior::Search if sighting.is_some() => self.enter(Behavior::Chase),
+            _ => {}
+        }
+
+        match self.behavior {
+            Behavior::Patrol => self.drive_route(position, self.speeds.patrol),
+            Behavior::Alert => self.last_known_position.map_or(Intent::Idle, Intent::Face),
+            Behavior::Chase => self.drive_to_last_known(self.speeds.chase),
+            Behavior::Search => {
+                let Some(target) = self.last_known_position else {
+                    self.enter(Behavior::Return);
+                    return self.drive_route(position, self.speeds.return_to_patrol);
+                };
+                if !arrived(position, target) {
+                    return self.drive_to_last_known(self.speeds.search);
+                }
+                self.search_time_left -= dt;
+                if self.search_time_left <= 0.0 {
+                    self.last_known_position = None;
+                    self.enter(Behavior::Return);
+                    return self.drive_route(position, self.speeds.return_to_patrol);
+                }
+                Intent::LookAround
+            }
+            Behavior::Return => {
+                let Some(&waypoint) = self.patrol_route.get(self.next_waypoint) else {
+                    self.enter(Behavior::Patrol);
+                    return Intent::Idle;
+                };
+                if arrived(position, waypoint) {
+                    self.enter(Behavior::Patrol);
+                    return self.drive_route(position, self.speeds.patrol);
+                }
+                Intent::MoveTo { target: waypoint, speed: self.speeds.return_to_patrol }
+            }
+        }
+    }
+
+    /// Snapshot of the brain for a save game. Stimuli not yet ticked are dropped.
+    pub fn save(&self) -> BrainState {
+        BrainState {
+            behavior: self.behavior,
+            patrol_route: self.patrol_route.iter().map(|point| point.to_array()).collect(),
+            next_waypoint: self.next_waypoint,
+            last_known_position: self.last_known_position.map(|point| point.to_array()),
+            time_in_behavior: self.time_in_behavior,
+            search_time_left: self.search_time_left,
+        }
+    }
+
+    /// Brain picking up where a saved one left off
+    pub fn restore(state: &BrainState, speeds: BehaviorSpeeds) -> Self {
+        let patrol_route: Vec<Vec3> = state.patrol_route.iter().copied().map(Vec3::from_array).collect();
+        Self {
+            behavior: state.behavior,
+            speeds,
+            next_waypoint: state.next_waypoint.min(patrol_route.len().saturating_sub(1)),
+            patrol_route,
+            last_known_position: state.last_known_position.map(Vec3::from_array),
+            sighting: None,
+            report: None,
+            noise: None,
+            time_in_behavior: state.time_in_behavior,
+            search_time_left: state.search_time_left,
+        }
+    }
+
+    fn enter(&mut self, behavior: Behavior) {
+        self.behavior = behavior;
+        self.time_in_behavior = 0.0;
+        if behavior == Behavior::Search {
+            self.search_time_left = SEARCH_DURATION;
+        }
+    }
+
+    /// Heads for the current patrol waypoint, moving on to the next once reached
+    fn drive_route(&mut self, position: Vec3, speed: f32) -> Intent {
+        if self.patrol_route.is_empty() {
+            return Intent::Idle;
+        }
+        if arrived(position, self.patrol_route[self.next_waypoint]) {
+            self.next_waypoint = (self.next_waypoint + 1) % self.patrol_route.len();
+        }
+        Intent::MoveTo { target: self.patrol_route[self.next_waypoint], speed }
+    }
+
+    fn drive_to_last_known(&self, speed: f32) -> Intent {
+        self.last_known_position
+            .map_or(Intent::Idle, |target| Intent::MoveTo { target, speed })
+    }
+}
+
+/// Saved form of a [`UgvBrain`]; speeds come from the UGV's archetype
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrainState {
+    pub behavior: Behavior,
+    pub patrol_route: Vec<[f32; 3]>,
+    pub next_waypoint: usize,
+    pub last_known_position: Option<[f32; 3]>,
+    pub time_in_behavior: f32,
+    pub search_time_left: f32,
+}
+
+fn arrived(position: Vec3, target: Vec3) -> bool {
+    position.xz().distance(target.xz()) < ARRIVE_DISTANCE
+}
+
+/// Loop of four waypoints around `center` with a random radius and rotation.
+///
+/// Waypoints that land in an obstacle or off the ground are re-rolled a few
+/// times with a new radius and a slightly turned angle, then left at `center`.
+pub fn patrol_loop(center: Vec3, nav: &NavGrid, rng: &mut WorldRng) -> Vec<Vec3> {
+    let start = rng.angle();
+    (0..4)
+        .map(|i| {
+            let angle = start + i as f32 * std::f32::consts::FRAC_PI_2;
+            (0..PATROL_ATTEMPTS)
+                .map(|attempt| {
+                    let jitter = if attempt == 0 { 0.0 } else { rng.range(-0.5, 0.5) };
+                    let radius = rng.range(8.0, 15.0);
+                    let angle = angle + jitter;
+                    center + Vec3::new(angle.cos() * radius, 0.0, angle.sin() * radius)
+                })
+                .find(|point| nav.is_walkable(nav.cell_of(*point)))
+                .unwrap_or(center)
+        })
+        .collect()
+}
+
+/// Tells every UGV that can see the player, within its archetype's sight range, where it is
+pub(crate) fn see_player(
+    mut perceived: MessageWriter<Perceived>,
+    mut sphere_query: Query<(Entity, &Transform, &Enemy, &mut ChasingSphere)>,
+    cube_query: Query<&Transform, (With<CubeController>, Without<ChasingSphere>)>,
+    obstacles: Obstacles,
+) {
+    let Ok(cube_transform) = cube_query.single() else {
+        return;
+    };
+    let cube_pos = cube_transform.translation;
+    for (entity, transform, enemy, mut sphere) in sphere_query.iter_mut() {
+        let sphere_pos = transform.translation;
+        let has_line_of_sight = cube_pos.distance(sphere_pos) < enemy.sight_range
+            && obstacles.line_of_sight(sphere_pos, cube_pos, None);
+        if has_line_of_sight {
+            perceived.write(Perceived { ugv: entity, stimulus: Stimulus::Sight(cube_pos) });
+        }
+        sphere.last_line_of_sight = has_line_of_sight;
+    }
+}
+
+/// Hands this frame's perception events to the brains they are addressed to
+pub(crate) fn deliver_perceptions(mut perceived: MessageReader<Perceived>, mut brains: Query<&mut UgvBrain>) {
+    for event in perceived.read() {
+        if let Ok(mut brain) = brains.get_mut(event.ugv) {
+            brain.perceive(event.stimulus);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 0.1;
+
+    fn route() -> Vec<Vec3> {
+        vec![
+            Vec3::new(10.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 10.0),
+            Vec3::new(-10.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -10.0),
+        ]
+    }
+
+    fn speeds() -> BehaviorSpeeds {
+        BehaviorSpeeds::from_chase_speed(5.0)
+    }
+
+    fn brain() -> UgvBrain {
+        UgvBrain::new(route(), speeds())
+    }
+
+    #[test]
+    fn full_cycle_from_patrol_back_to_patrol() {
+        let mut brain = brain();
+        let player = Vec3::new(5.0, 0.0, 5.0);
+
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::MoveTo { target: route()[0], speed: speeds().patrol });
+        assert_eq!(brain.behavior(), Behavior::Patrol);
+
+        brain.perceive(Stimulus::Sight(player));
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::Face(player));
+        assert_eq!(brain.behavior(), Behavior::Alert);
+
+        brain.perceive(Stimulus::Sight(player));
+        assert_eq!(brain.tick(Vec3::ZERO, ALERT_DURATION), Intent::MoveTo { target: player, speed: speeds().chase });
+        assert_eq!(brain.behavior(), Behavior::Chase);
+
+        // Losing sight sends it to where the player was last seen
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::MoveTo { target: player, speed: speeds().search });
+        assert_eq!(brain.behavior(), Behavior::Search);
+        assert_eq!(brain.last_known_position(), Some(player));
+
+        assert_eq!(brain.tick(player, 1.0), Intent::LookAround);
+        assert_eq!(
+            brain.tick(player, SEARCH_DURATION),
+            Intent::MoveTo { target: route()[0], speed: speeds().return_to_patrol }
+        );
+        assert_eq!(brain.behavior(), Behavior::Return);
+        assert_eq!(brain.last_known_position(), None);
+
+        assert_eq!(brain.tick(route()[0], DT), Intent::MoveTo { target: route()[1], speed: speeds().patrol });
+        assert_eq!(brain.behavior(), Behavior::Patrol);
+    }
+
+    #[test]
+    fn alert_without_sighting_turns_into_search() {
+        let mut brain = brain();
+        let player = Vec3::new(5.0, 0.0, 5.0);
+        brain.perceive(Stimulus::Sight(player));
+        brain.tick(Vec3::ZERO, DT);
+
+        assert_eq!(brain.tick(Vec3::ZERO, ALERT_DURATION * 0.5), Intent::Face(player));
+        assert_eq!(brain.behavior(), Behavior::Alert);
+        assert_eq!(brain.tick(Vec3::ZERO, ALERT_DURATION), Intent::MoveTo { target: player, speed: speeds().search });
+        assert_eq!(brain.behavior(), Behavior::Search);
+    }
+
+    #[test]
+    fn sighting_during_search_resumes_chase() {
+        let mut brain = brain();
+        brain.perceive(Stimulus::Alarm(Vec3::new(20.0, 0.0, 0.0)));
+        brain.tick(Vec3::ZERO, DT);
+        assert_eq!(brain.behavior(), Behavior::Search);
+
+        let player = Vec3::new(-5.0, 0.0, 0.0);
+        brain.perceive(Stimulus::Sight(player));
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::MoveTo { target: player, speed: speeds().chase });
+        assert_eq!(brain.behavior(), Behavior::Chase);
+    }
+
+    #[test]
+    fn alarm_sends_patrol_straight_to_search() {
+        let mut brain = brain();
+        let reported = Vec3::new(30.0, 0.0, -4.0);
+        brain.perceive(Stimulus::Alarm(reported));
+
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::MoveTo { target: reported, speed: speeds().search });
+        assert_eq!(brain.behavior(), Behavior::Search);
+        assert_eq!(brain.last_known_position(), Some(reported));
+    }
+
+    #[test]
+    fn noise_makes_patrol_stop_and_listen() {
+        let mut brain = brain();
+        let heard = Vec3::new(-3.0, 0.0, 8.0);
+        brain.perceive(Stimulus::Noise(heard));
+
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::Face(heard));
+        assert_eq!(brain.behavior(), Behavior::Alert);
+
+        // A second noise while alert only turns it towards the newer sound
+        let later = Vec3::new(-4.0, 0.0, 9.0);
+        brain.perceive(Stimulus::Noise(later));
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::Face(later));
+        assert_eq!(brain.tick(Vec3::ZERO, ALERT_DURATION), Intent::MoveTo { target: later, speed: speeds().search });
+        assert_eq!(brain.behavior(), Behavior::Search);
+    }
+
+    #[test]
+    fn noise_does_not_distract_a_chase() {
+        let mut brain = brain();
+        let player = Vec3::new(5.0, 0.0, 5.0);
+        brain.perceive(Stimulus::Sight(player));
+        brain.tick(Vec3::ZERO, DT);
+        brain.perceive(Stimulus::Sight(player));
+        brain.tick(Vec3::ZERO, ALERT_DURATION);
+        assert_eq!(brain.behavior(), Behavior::Chase);
+
+        brain.perceive(Stimulus::Sight(player));
+        brain.perceive(Stimulus::Noise(Vec3::new(-20.0, 0.0, 0.0)));
+        assert_eq!(brain.tick(Vec3::ZERO, DT), Intent::MoveTo { target: player, speed: speeds().chase });
+        assert_eq!(brain.behavior(), Behavior::Chase);
+    }
+
+    #[test]
+    fn search_times_out_only_once_at_the_target() {
+        let mut brain = brain();
+        let reported = Vec3::new(30.0, 0.0, 0.0);
+        brain.perceive(Stimulus::Alarm(reported));
+        brain.tick(Vec3::ZERO, DT);
+
+        // Time spent driving there does not count towards the search
+        brain.tick(Vec3::ZERO, SEARCH_DURATION * 2.0);
+        assert_eq!(brain.behavior(), Behavior::Search);
+
+        assert_eq!(brain.tick(reported, SEARCH_DURATION - 0.5), Intent::LookAround);
+        assert_eq!(brain.last_known_position(), Some(reported));
+        brain.tick(reported, 1.0);
+        assert_eq!(brain.behavior(), Behavior::Return);
+        assert_eq!(brain.last_known_position(), None);
+    }
+
+    #[test]
+    fn patrol_loop_falls_back_to_center_off_the_ground() {
+        let mut rng = WorldRng::new(7);
+        let center = Vec3::new(0.5, 0.0, 0.5);
+        // Every waypoint radius lies beyond this tiny ground
+        let route = patrol_loop(center, &NavGrid::new(4.0), &mut rng);
+        assert_eq!(route, vec![center; 4]);
+    }
+
+    #[test]
+    fn patrol_loop_keeps_waypoints_on_the_grid() {
+        let nav = NavGrid::new(24.0);
+        for seed in 0..32 {
+            let mut rng = WorldRng::new(seed);
+            for point in patrol_loop(Vec3::ZERO, &nav, &mut rng) {
+                assert!(point == Vec3::ZERO || nav.is_walkable(nav.cell_of(point)), "{point} is off the grid");
+            }
+        }
+    }
+}
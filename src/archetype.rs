@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::WorldRng;
+
+/// Every kind of enemy a level can spawn, keyed by name.
+///
+/// Loaded from a `.archetypes.ron` file, e.g.
+/// `assets/enemies/default.archetypes.ron`, and referenced by name from the
+/// waves of a level.
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct ArchetypeRegistry {
+    pub archetypes: HashMap<String, ArchetypeDef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArchetypeDef {
+    /// glTF scene path, e.g. `"models/antagonists/ugv/ugv.gltf#Scene0"`
+    pub model: String,
+    pub scale: f32,
+    /// Chase speed in units per second; calmer behaviors drive slower
+    pub speed: f32,
+    /// Degrees per second
+    pub turn_rate: f32,
+    /// Health taken from the player on contact
+    pub damage: f32,
+    /// Distance to the player at which the enemy hits
+    pub contact_radius: f32,
+    /// How far the enemy can see the player
+    pub sight_range: f32,
+    /// Multiplier on the distance noises carry to this enemy
+    #[serde(default = "default_hearing")]
+    pub hearing: f32,
+    #[serde(default)]
+    pub death_effect: DeathEffect,
+}
+
+/// What is left behind when an enemy is destroyed
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub enum DeathEffect {
+    #[default]
+    None,
+    /// A ring of `count` rising smoke puffs of `size` radius
+    Smoke { count: usize, size: f32, color: [f32; 3], lifetime: f32 },
+}
+
+fn default_hearing() -> f32 {
+    1.0
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchetypeLoaderError {
+    #[error("could not read archetype file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse archetype file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+pub struct ArchetypeLoader;
+
+impl AssetLoader for ArchetypeLoader {
+    type Asset = ArchetypeRegistry;
+    type Settings = ();
+    type Error = ArchetypeLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["archetypes.ron"]
+    }
+}
+
+/// One archetype a wave can pick, with its model already requested
+#[derive(Debug, Clone)]
+pub struct SpawnChoice {
+    pub name: String,
+    pub weight: f32,
+    pub archetype: ArchetypeDef,
+    pub model: Handle<Scene>,
+}
+
+/// Picks a choice with probability proportional to its weight
+pub fn pick<'a>(choices: &'a [SpawnChoice], rng: &mut WorldRng) -> Option<&'a SpawnChoice> {
+    let total: f32 = choices.iter().map(|choice| choice.weight.max(0.0)).sum();
+    let mut roll = rng.range(0.0, total);
+    for choice in choices {
+        roll -= choice.weight.max(0.0);
+        if roll < 0.0 {
+            return Some(choice);
+        }
+    }
+    // Rounding can leave the roll a hair past the end
+    choices.iter().rev().find(|choice| choice.weight > 0.0)
+}
+
+/// Combat and perception stats of a spawned enemy, copied from its archetype
+#[derive(Component, Debug, Clone)]
+pub struct Enemy {
+    pub archetype: String,
+    pub damage: f32,
+    pub contact_radius: f32,
+    pub sight_range: f32,
+    pub hearing: f32,
+    pub death_effect: DeathEffect,
+}
+
+impl Enemy {
+    pub fn new(name: &str, archetype: &ArchetypeDef) -> Self {
+        Self {
+            archetype: name.to_string(),
+            damage: archetype.damage,
+            contact_radius: archetype.contact_radius,
+            sight_range: archetype.sight_range,
+            hearing: archetype.hearing,
+            death_effect: archetype.death_effect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shipped() -> ArchetypeRegistry {
+        ron::from_str(include_str!("../assets/enemies/default.archetypes.ron")).unwrap()
+    }
+
+    fn choices(weights: &[f32]) -> Vec<SpawnChoice> {
+        let archetype = shipped().archetypes["ugv"].clone();
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, &weight)| SpawnChoice {
+                name: i.to_string(),
+                weight,
+                archetype: archetype.clone(),
+                model: Handle::default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shipped_archetypes_parse() {
+        let registry = shipped();
+        for name in ["ugv", "scout", "heavy"] {
+            let def = &registry.archetypes[name];
+            assert!(def.scale > 0.0 && def.speed > 0.0 && def.turn_rate > 0.0, "{name}: {def:?}");
+            assert!(def.damage > 0.0 && def.contact_radius > 0.0 && def.sight_range > 0.0, "{name}: {def:?}");
+        }
+        // Fields left out take their defaults
+        assert_eq!(registry.archetypes["ugv"].hearing, 1.0);
+        assert_eq!(registry.archetypes["scout"].hearing, 1.5);
+        assert!(matches!(registry.archetypes["ugv"].death_effect, DeathEffect::Smoke { count: 8, .. }));
+    }
+
+    #[test]
+    fn pick_from_nothing_is_none() {
+        let mut rng = WorldRng::new(1);
+        assert!(pick(&[], &mut rng).is_none());
+        assert!(pick(&choices(&[0.0, 0.0]), &mut rng).is_none());
+        assert!(pick(&choices(&[-1.0]), &mut rng).is_none());
+    }
+
+    #[test]
+    fn pick_skips_zero_and_negative_weights() {
+        let choices = choices(&[0.0, 1.0, -2.0, 0.0]);
+        let mut rng = WorldRng::new(2);
+        for _ in 0..1_000 {
+            assert_eq!(pick(&choices, &mut rng).unwrap().name, "1");
+        }
+    }
+
+    #[test]
+    fn pick_follows_the_weights() {
+        let choices = choices(&[6.0, 3.0, 1.0]);
+        let mut rng = WorldRng::new(3);
+        let mut counts = [0usize; 3];
+        for _ in 0..10_000 {
+            counts[pick(&choices, &mut rng).unwrap().name.parse::<usize>().unwrap()] += 1;
+        }
+        for (count, expected) in counts.into_iter().zip([6_000.0, 3_000.0, 1_000.0]) {
+            assert!((count as f32 - expected).abs() < expected * 0.1, "{counts:?}");
+        }
+    }
+
+    #[test]
+    fn pick_is_repeatable_for_a_seed() {
+        let choices = choices(&[1.0, 1.0, 1.0, 1.0]);
+        let run = |seed| {
+            let mut rng = WorldRng::new(seed);
+            (0..20).map(|_| pick(&choices, &mut rng).unwrap().name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(run(9), run(9));
+        assert_ne!(run(9), run(10));
+    }
+}
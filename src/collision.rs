@@ -0,0 +1,572 @@
+use bevy::camera::primitives::MeshAabb;
+use bevy::ecs::system::SystemParam;
+use bevy::math::Affine3A;
+use bevy::prelude::*;
+use bevy::scene::SceneInstanceReady;
+use serde::Deserialize;
+
+use crate::spatial::ObstacleIndex;
+use crate::{ChasingSphere, CubeController};
+
+/// Shape of an [`ObstacleBlocker`] in the entity's local space.
+///
+/// Every query applies the entity's full `Transform`, so boxes rotate with the
+/// entity and all shapes scale with it. Round shapes stand along local Y.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColliderShape {
+    /// Oriented box
+    Box { half_size: Vec3 },
+    Cylinder { radius: f32, half_height: f32 },
+    Sphere { radius: f32 },
+    /// Cylinder of `2 * half_length` capped by hemispheres of `radius`
+    Capsule { radius: f32, half_length: f32 },
+}
+
+/// A solid volume that blocks movement and line of sight
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ObstacleBlocker {
+    pub shape: ColliderShape,
+    /// Center of the shape in the entity's local space
+    pub center: Vec3,
+}
+
+impl ObstacleBlocker {
+    pub fn new(shape: ColliderShape) -> Self {
+        Self { shape, center: Vec3::ZERO }
+    }
+
+    pub fn cuboid(half_size: Vec3) -> Self {
+        Self::new(ColliderShape::Box { half_size })
+    }
+}
+
+/// How an [`AutoCollider`] wraps the bounds of its scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum AutoFit {
+    Box,
+    /// Upright cylinder around the widest horizontal extent
+    Cylinder,
+    /// Sphere through the corners of the bounds
+    Sphere,
+}
+
+/// Swapped for an [`ObstacleBlocker`] fitted to the entity's glTF scene once the
+/// scene has spawned
+#[derive(Component, Debug, Clone, Copy)]
+pub struct AutoCollider {
+    pub fit: AutoFit,
+}
+
+/// Marks the ground plane's blocker so it is not treated as a wall
+#[derive(Component)]
+pub struct Ground;
+
+impl ColliderShape {
+    /// Half extents of the local-space bounding box
+    pub fn half_extents(&self) -> Vec3 {
+        match *self {
+            ColliderShape::Box { half_size } => half_size,
+            ColliderShape::Cylinder { radius, half_height } => Vec3::new(radius, half_height, radius),
+            ColliderShape::Sphere { radius } => Vec3::splat(radius),
+            ColliderShape::Capsule { radius, half_length } => Vec3::new(radius, half_length + radius, radius),
+        }
+    }
+
+    /// Entry distance along `dir` (need not be normalized) and outward local normal.
+    /// Rays starting inside the shape never hit it.
+    fn ray_cast(&self, origin: Vec3, dir: Vec3, max_distance: f32) -> Option<(f32, Vec3)> {
+        match *self {
+            ColliderShape::Box { half_size } => ray_aabb(origin, dir, (-half_size, half_size), max_distance),
+            ColliderShape::Sphere { radius } => ray_sphere(origin, dir, Vec3::ZERO, radius, max_distance),
+            ColliderShape::Cylinder { radius, half_height } => {
+                if origin.xz().length_squared() < radius * radius && origin.y.abs() < half_height {
+                    return None;
+                }
+                let side = ray_cylinder_side(origin, dir, radius, half_height, max_distance);
+                let cap = ray_cylinder_cap(origin, dir, radius, half_height, max_distance);
+                nearest(side, cap)
+            }
+            ColliderShape::Capsule { radius, half_length } => {
+                let axis_point = Vec3::new(0.0, origin.y.clamp(-half_length, half_length), 0.0);
+                if origin.distance_squared(axis_point) < radius * radius {
+                    return None;
+                }
+                let side = ray_cylinder_side(origin, dir, radius, half_length, max_distance);
+                let top = ray_sphere(origin, dir, Vec3::Y * half_length, radius, max_distance);
+                let bottom = ray_sphere(origin, dir, Vec3::NEG_Y * half_length, radius, max_distance);
+                nearest(nearest(side, top), bottom)
+            }
+        }
+    }
+
+    /// Nearest local-space surface point to `point`, and whether `point` is inside
+    fn closest_surface_point(&self, point: Vec3) -> (Vec3, bool) {
+        match *self {
+            ColliderShape::Box { half_size } => {
+                let clamped = point.clamp(-half_size, half_size);
+                if clamped != point {
+                    return (clamped, false);
+                }
+                // Inside: project onto the nearest face
+                let depth = half_size - point.abs();
+                let axis = if depth.x <= depth.y && depth.x <= depth.z {
+                    0
+                } else if depth.y <= depth.z {
+                    1
+                } else {
+                    2
+                };
+                let mut surface = point;
+                surface[axis] = half_size[axis].copysign(point[axis]);
+                (surface, true)
+            }
+            ColliderShape::Sphere { radius } => {
+                let dir = point.try_normalize().unwrap_or(Vec3::X);
+                (dir * radius, point.length_squared() < radius * radius)
+            }
+            ColliderShape::Cylinder { radius, half_height } => {
+                let radial = point.xz();
+                let inside = radial.length_squared() < radius * radius && point.y.abs() < half_height;
+                let radial_dir = radial.try_normalize().unwrap_or(Vec2::X);
+                if !inside {
+                    let radial = radial.clamp_length_max(radius);
+                    return (Vec3::new(radial.x, point.y.clamp(-half_height, half_height), radial.y), false);
+                }
+                if radius - radial.length() < half_height - point.y.abs() {
+                    let side = radial_dir * radius;
+                    (Vec3::new(side.x, point.y, side.y), true)
+                } else {
+                    (Vec3::new(point.x, half_height.copysign(point.y), point.z), true)
+                }
+            }
+            ColliderShape::Capsule { radius, half_length } => {
+                let axis_point = Vec3::new(0.0, point.y.clamp(-half_length, half_length), 0.0);
+                let offset = point - axis_point;
+                let dir = offset.try_normalize().unwrap_or(Vec3::X);
+                (axis_point + dir * radius, offset.length_squared() < radius * radius)
+            }
+        }
+    }
+}
+
+/// A blocker's shape placed in the world by its entity's transform
+#[derive(Debug, Clone, Copy)]
+pub struct PlacedCollider {
+    pub entity: Entity,
+    pub shape: ColliderShape,
+    to_world: Affine3A,
+    to_local: Affine3A,
+}
+
+impl PlacedCollider {
+    pub fn new(entity: Entity, transform: &Transform, blocker: &ObstacleBlocker) -> Self {
+        let to_world = transform.compute_affine() * Affine3A::from_translation(blocker.center);
+        Self { entity, shape: blocker.shape, to_world, to_local: to_world.inverse() }
+    }
+
+    /// World-space `(min, max)` bounds
+    pub fn aabb(&self) -> (Vec3, Vec3) {
+        let center = Vec3::from(self.to_world.translation);
+        let m = self.to_world.matrix3;
+        let abs = Mat3::from_cols(m.x_axis.abs().into(), m.y_axis.abs().into(), m.z_axis.abs().into());
+        let half = abs * self.shape.half_extents();
+        (center - half, center + half)
+    }
+
+    /// Entry distance along normalized `dir` and world-space outward normal
+    pub fn ray_cast(&self, origin: Vec3, dir: Vec3, max_distance: f32) -> Option<(f32, Vec3)> {
+        // The local direction keeps its world length, so distances carry over unchanged
+        let local_origin = self.to_local.transform_point3(origin);
+        let local_dir = self.to_local.transform_vector3(dir);
+        let (distance, local_normal) = self.shape.ray_cast(local_origin, local_dir, max_distance)?;
+        Some((distance, self.normal_to_world(local_normal)))
+    }
+
+    /// Push that moves a sphere at `center` out of this collider, if they overlap
+    pub fn depenetrate(&self, center: Vec3, radius: f32) -> Option<Vec3> {
+        let (local_surface, inside) = self.shape.closest_surface_point(self.to_local.transform_point3(center));
+        let surface = self.to_world.transform_point3(local_surface);
+        let offset = center - surface;
+        let distance = offset.length();
+        if inside {
+            let out = (-offset).try_normalize()?;
+            Some(out * (distance + radius))
+        } else if distance < radius {
+            let out = offset.try_normalize()?;
+            Some(out * (radius - distance))
+        } else {
+            None
+        }
+    }
+
+    fn normal_to_world(&self, local_normal: Vec3) -> Vec3 {
+        // Inverse transpose of the linear part keeps normals perpendicular under scale
+        (self.to_local.matrix3.transpose() * Vec3A::from(local_normal))
+            .normalize_or_zero()
+            .into()
+    }
+}
+
+/// First obstacle hit by a ray
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    /// Distance from the ray origin to the hit point
+    pub distance: f32,
+    /// Outward surface normal at the hit point
+    pub normal: Vec3,
+}
+
+/// Slab test of a ray against an axis-aligned box.
+///
+/// Distances are in units of `dir`'s length. Returns the entry distance and the normal
+/// of the face entered, or `None` if the box is missed, lies beyond `max_distance`,
+/// or contains the origin.
+pub fn ray_aabb(origin: Vec3, dir: Vec3, (min, max): (Vec3, Vec3), max_distance: f32) -> Option<(f32, Vec3)> {
+    let mut t_enter = f32::NEG_INFINITY;
+    let mut t_exit = f32::INFINITY;
+    let mut normal = Vec3::ZERO;
+
+    for axis in 0..3 {
+        if dir[axis].abs() < f32::EPSILON {
+            // Parallel to this slab: miss unless the origin lies between its planes
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let inv = 1.0 / dir[axis];
+        let mut t_near = (min[axis] - origin[axis]) * inv;
+        let mut t_far = (max[axis] - origin[axis]) * inv;
+        if t_near > t_far {
+            std::mem::swap(&mut t_near, &mut t_far);
+        }
+
+        if t_near > t_enter {
+            t_enter = t_near;
+            normal = Vec3::ZERO;
+            normal[axis] = -dir[axis].signum();
+        }
+        t_exit = t_exit.min(t_far);
+        if t_enter > t_exit {
+            return None;
+        }
+    }
+
+    (t_enter >= 0.0 && t_enter <= max_distance).then_some((t_enter, normal))
+}
+
+fn ray_sphere(origin: Vec3, dir: Vec3, center: Vec3, radius: f32, max_distance: f32) -> Option<(f32, Vec3)> {
+    let offset = origin - center;
+    let a = dir.length_squared();
+    let b = offset.dot(dir);
+    let c = offset.length_squared() - radius * radius;
+    let discriminant = b * b - a * c;
+    if c < 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / a;
+    (t >= 0.0 && t <= max_distance).then(|| (t, (offset + dir * t) / radius))
+}
+
+fn ray_cylinder_side(origin: Vec3, dir: Vec3, radius: f32, half_height: f32, max_distance: f32) -> Option<(f32, Vec3)> {
+    let a = dir.xz().length_squared();
+    if a < f32::EPSILON {
+        return None;
+    }
+    let b = origin.xz().dot(dir.xz());
+    let c = origin.xz().length_squared() - radius * radius;
+    let discriminant = b * b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / a;
+    let hit = origin + dir * t;
+    (t >= 0.0 && t <= max_distance && hit.y.abs() <= half_height)
+        .then(|| (t, Vec3::new(hit.x, 0.0, hit.z) / radius))
+}
+
+fn ray_cylinder_cap(origin: Vec3, dir: Vec3, radius: f32, half_height: f32, max_distance: f32) -> Option<(f32, Vec3)> {
+    if dir.y.abs() < f32::EPSILON {
+        return None;
+    }
+    // The cap facing the ray is the one on the side it comes from
+    let side = -dir.y.signum();
+    let t = (side * half_height - origin.y) / dir.y;
+    let hit = origin + dir * t;
+    (t >= 0.0 && t <= max_distance && hit.xz().length_squared() <= radius * radius)
+        .then(|| (t, Vec3::Y * side))
+}
+
+fn nearest(a: Option<(f32, Vec3)>, b: Option<(f32, Vec3)>) -> Option<(f32, Vec3)> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Everything with a blocker that is not the ground or a moving actor
+pub(crate) type ObstacleFilter = (Without<Ground>, Without<CubeController>, Without<ChasingSphere>);
+
+/// Read access to every obstacle except the ground, the player and the UGVs.
+///
+/// Shared by movement, line of sight and AI so they all agree on what is solid.
+/// Queries go through the [`ObstacleIndex`] grid, which lags blocker changes made
+/// earlier in the same frame.
+#[derive(SystemParam)]
+pub struct Obstacles<'w> {
+    index: Res<'w, ObstacleIndex>,
+}
+
+impl Obstacles<'_> {
+    /// Obstacles whose bounds come within `radius` of `center` on the XZ plane
+    pub fn colliders_near(&self, center: Vec3, radius: f32) -> Vec<PlacedCollider> {
+        self.index.colliders_near(center, radius)
+    }
+
+    /// Nearest obstacle along `dir` (normalized) within `max_distance`.
+    ///
+    /// `ignore` skips one collider, such as the one of a prop looking out from
+    /// inside or on the surface of its own bounds.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_distance: f32, ignore: Option<Entity>) -> Option<RayHit> {
+        self.index.cast_ray(origin, dir, max_distance, ignore)
+    }
+
+    /// Whether nothing solid lies on the segment from `from` to `to`, apart from
+    /// `ignore`'s collider
+    pub fn line_of_sight(&self, from: Vec3, to: Vec3, ignore: Option<Entity>) -> bool {
+        self.index.line_of_sight(from, to, ignore)
+    }
+
+    /// Whether a sphere of `radius` at `center` overlaps any obstacle
+    pub fn overlaps(&self, center: Vec3, radius: f32) -> bool {
+        self.index
+            .colliders_near(center, radius)
+            .iter()
+            .any(|collider| collider.depenetrate(center, radius).is_some())
+    }
+}
+
+/// Moves a sphere of `radius` from `position` by `delta` on the XZ plane, then pushes
+/// it back out of any collider it ended up in.
+///
+/// Pushes are horizontal and along the surface normal, so the part of the motion
+/// tangent to a wall survives and the mover slides along it.
+pub fn slide_move(position: Vec3, delta: Vec3, radius: f32, colliders: &[PlacedCollider]) -> Vec3 {
+    let mut resolved = position + Vec3::new(delta.x, 0.0, delta.z);
+
+    // A few passes settle corners where two colliders push against each other
+    for _ in 0..3 {
+        let mut moved = false;
+        for collider in colliders {
+            let Some(push) = collider.depenetrate(resolved, radius) else {
+                continue;
+            };
+            let push = Vec3::new(push.x, 0.0, push.z);
+            if push.length_squared() > f32::EPSILON {
+                resolved += push;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    resolved
+}
+
+/// Draws every collider except the ground as a wireframe
+pub(crate) fn draw_colliders(
+    mut gizmos: Gizmos,
+    colliders: Query<(&Transform, &ObstacleBlocker), Without<Ground>>,
+) {
+    let color = Color::srgb(0.0, 0.8, 0.3);
+    for (transform, blocker) in colliders.iter() {
+        let scale = transform.scale;
+        let isometry = Isometry3d::new(transform.transform_point(blocker.center), transform.rotation);
+        match blocker.shape {
+            ColliderShape::Box { half_size } => {
+                gizmos.primitive_3d(&Cuboid { half_size: half_size * scale }, isometry, color);
+            }
+            ColliderShape::Cylinder { radius, half_height } => {
+                let radius = radius * scale.x.max(scale.z);
+                gizmos.primitive_3d(&Cylinder { radius, half_height: half_height * scale.y }, isometry, color);
+            }
+            ColliderShape::Sphere { radius } => {
+                gizmos.primitive_3d(&Sphere { radius: radius * scale.max_element() }, isometry, color);
+            }
+            ColliderShape::Capsule { radius, half_length } => {
+                let radius = radius * scale.x.max(scale.z);
+                gizmos.primitive_3d(&Capsule3d { radius, half_length: half_length * scale.y }, isometry, color);
+            }
+        }
+    }
+}
+
+/// Fits an [`AutoCollider`] to the meshes of the scene that just spawned under it.
+///
+/// Bounds are taken in the root's local space, so the blocker follows the root's
+/// transform like any hand-placed one.
+pub(crate) fn fit_auto_collider(
+    ready: On<SceneInstanceReady>,
+    mut commands: Commands,
+    auto_colliders: Query<&AutoCollider>,
+    children: Query<&Children>,
+    nodes: Query<(&Transform, Option<&Mesh3d>, &ChildOf)>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    let root = ready.entity;
+    let Ok(auto) = auto_colliders.get(root) else {
+        return;
+    };
+
+    let mut min = Vec3::INFINITY;
+    let mut max = Vec3::NEG_INFINITY;
+    for node in children.iter_descendants(root) {
+        let Ok((_, Some(mesh), _)) = nodes.get(node) else {
+            continue;
+        };
+        let Some(aabb) = meshes.get(&mesh.0).and_then(|mesh| mesh.compute_aabb()) else {
+            continue;
+        };
+
+        // Chain local transforms up to the root
+        let mut to_root = Affine3A::IDENTITY;
+        let mut current = node;
+        while current != root {
+            let Ok((transform, _, child_of)) = nodes.get(current) else {
+                break;
+            };
+            to_root = transform.compute_affine() * to_root;
+            current = child_of.parent();
+        }
+
+        let center = Vec3::from(aabb.center);
+        let half = Vec3::from(aabb.half_extents);
+        for corner in 0..8 {
+            let sign = Vec3::new(
+                if corner & 1 == 0 { -1.0 } else { 1.0 },
+                if corner & 2 == 0 { -1.0 } else { 1.0 },
+                if corner & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            let point = to_root.transform_point3(center + half * sign);
+            min = min.min(point);
+            max = max.max(point);
+        }
+    }
+
+    let mut entity = commands.entity(root);
+    entity.remove::<AutoCollider>();
+    if min.cmpgt(max).any() {
+        warn!("Auto collider on {} found no meshes to fit", root);
+        return;
+    }
+
+    let half = (max - min) * 0.5;
+    let shape = match auto.fit {
+        AutoFit::Box => ColliderShape::Box { half_size: half },
+        AutoFit::Cylinder => ColliderShape::Cylinder { radius: half.x.max(half.z), half_height: half.y },
+        AutoFit::Sphere => ColliderShape::Sphere { radius: half.length() },
+    };
+    entity.insert(ObstacleBlocker { shape, center: (min + max) * 0.5 });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNIT: (Vec3, Vec3) = (Vec3::splat(-1.0), Vec3::splat(1.0));
+
+    #[test]
+    fn ray_aabb_hits_the_face_it_enters() {
+        let hit = ray_aabb(Vec3::new(-5.0, 0.5, 0.0), Vec3::X, UNIT, 10.0);
+        assert_eq!(hit, Some((4.0, Vec3::NEG_X)));
+        let hit = ray_aabb(Vec3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -2.0, 0.0), UNIT, 10.0);
+        // Distances are in units of the direction's length
+        assert_eq!(hit, Some((2.0, Vec3::Y)));
+    }
+
+    #[test]
+    fn ray_aabb_ignores_rays_starting_inside() {
+        assert_eq!(ray_aabb(Vec3::ZERO, Vec3::X, UNIT, 10.0), None);
+        assert_eq!(ray_aabb(Vec3::new(0.9, -0.9, 0.5), Vec3::NEG_Y, UNIT, 10.0), None);
+    }
+
+    #[test]
+    fn ray_aabb_hits_at_zero_distance_from_the_surface() {
+        // Entering from exactly on a face counts, leaving from it does not
+        assert_eq!(ray_aabb(Vec3::new(-1.0, 0.0, 0.0), Vec3::X, UNIT, 10.0), Some((0.0, Vec3::NEG_X)));
+        assert_eq!(ray_aabb(Vec3::new(-1.0, 0.0, 0.0), Vec3::NEG_X, UNIT, 10.0), None);
+    }
+
+    #[test]
+    fn ray_aabb_handles_axis_parallel_rays() {
+        // Zero direction components must not divide by zero
+        assert_eq!(ray_aabb(Vec3::new(-5.0, 2.0, 0.0), Vec3::X, UNIT, 10.0), None);
+        assert_eq!(ray_aabb(Vec3::new(-5.0, 0.0, -3.0), Vec3::X, UNIT, 10.0), None);
+        assert_eq!(ray_aabb(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z, UNIT, 10.0), Some((4.0, Vec3::Z)));
+        // Grazing an edge plane still hits
+        assert_eq!(ray_aabb(Vec3::new(-5.0, 1.0, 1.0), Vec3::X, UNIT, 10.0), Some((4.0, Vec3::NEG_X)));
+    }
+
+    #[test]
+    fn ray_aabb_hits_thin_boxes() {
+        let thin = (Vec3::new(-1.0, -0.001, -1.0), Vec3::new(1.0, 0.001, 1.0));
+        let (distance, normal) = ray_aabb(Vec3::new(0.0, 5.0, 0.0), Vec3::NEG_Y, thin, 10.0).unwrap();
+        assert!((distance - 4.999).abs() < 1e-5);
+        assert_eq!(normal, Vec3::Y);
+
+        let flat = (Vec3::new(-1.0, 0.0, -1.0), Vec3::new(1.0, 0.0, 1.0));
+        assert_eq!(ray_aabb(Vec3::new(0.5, 5.0, 0.5), Vec3::NEG_Y, flat, 10.0), Some((5.0, Vec3::Y)));
+        // Running along a flat box's plane from outside still finds its edge
+        assert_eq!(ray_aabb(Vec3::new(-5.0, 0.0, 0.0), Vec3::X, flat, 10.0), Some((4.0, Vec3::NEG_X)));
+    }
+
+    #[test]
+    fn ray_aabb_stops_at_max_distance() {
+        let origin = Vec3::new(-5.0, 0.0, 0.0);
+        assert_eq!(ray_aabb(origin, Vec3::X, UNIT, 3.9), None);
+        assert_eq!(ray_aabb(origin, Vec3::X, UNIT, 4.0), Some((4.0, Vec3::NEG_X)));
+        // A box entirely behind the origin is missed
+        assert_eq!(ray_aabb(origin, Vec3::NEG_X, UNIT, 100.0), None);
+    }
+
+    #[test]
+    fn placed_box_ray_cast_is_in_world_units() {
+        let transform = Transform::from_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2))
+            .with_scale(Vec3::splat(2.0));
+        let blocker = ObstacleBlocker::cuboid(Vec3::new(3.0, 1.0, 1.0));
+        let collider = PlacedCollider::new(Entity::PLACEHOLDER, &transform, &blocker);
+
+        // Turned a quarter around Y, the long side runs along Z and the box is 2 wide on X
+        let (distance, normal) = collider.ray_cast(Vec3::new(-10.0, 0.0, 0.0), Vec3::X, 20.0).unwrap();
+        assert!((distance - 8.0).abs() < 1e-4, "{distance}");
+        assert!(normal.abs_diff_eq(Vec3::NEG_X, 1e-5), "{normal}");
+
+        let (distance, _) = collider.ray_cast(Vec3::new(0.0, 0.0, 10.0), Vec3::NEG_Z, 20.0).unwrap();
+        assert!((distance - 4.0).abs() < 1e-4, "{distance}");
+
+        assert_eq!(collider.ray_cast(Vec3::new(-10.0, 0.0, 0.0), Vec3::X, 7.5), None);
+        assert_eq!(collider.ray_cast(Vec3::new(0.0, 0.0, 5.0), Vec3::X, 20.0), None);
+    }
+
+    #[test]
+    fn placed_colliders_ignore_rays_from_inside() {
+        let transform = Transform::from_xyz(3.0, 0.0, 3.0);
+        for shape in [
+            ColliderShape::Box { half_size: Vec3::splat(1.0) },
+            ColliderShape::Sphere { radius: 1.0 },
+            ColliderShape::Cylinder { radius: 1.0, half_height: 1.0 },
+            ColliderShape::Capsule { radius: 1.0, half_length: 0.5 },
+        ] {
+            let collider = PlacedCollider::new(Entity::PLACEHOLDER, &transform, &ObstacleBlocker::new(shape));
+            let inside = Vec3::new(3.2, 0.1, 2.9);
+            assert_eq!(collider.ray_cast(inside, Vec3::X, 10.0), None, "{shape:?}");
+            let outside = Vec3::new(-2.0, 0.0, 3.0);
+            let (distance, _) = collider.ray_cast(outside, Vec3::X, 10.0).unwrap();
+            assert!((distance - 4.0).abs() < 1e-4, "{shape:?} hit at {distance}");
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::state::RunStats;
+use crate::{CubeController, Health};
+
+/// What kind of harm a hit does; [`Defense`] can resist each kind separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum DamageKind {
+    /// An enemy running into its target
+    Contact,
+    /// Blasts. Nothing deals it yet, but levels can already set resistances to it
+    Explosion,
+    /// Hazards of the level itself. Like `Explosion`, only resistances use it so far
+    Environment,
+}
+
+/// Asks for `amount` health to be taken from `target`.
+///
+/// Every source of damage goes through this message; [`apply_damage`] is the
+/// only place that changes [`Health`].
+#[derive(Message, Debug, Clone, Copy)]
+pub struct DamageEvent {
+    pub target: Entity,
+    /// Damage before armor and resistances
+    pub amount: f32,
+    /// Who dealt the damage, if anyone. May already be despawned.
+    pub source: Option<Entity>,
+    pub kind: DamageKind,
+}
+
+/// An entity's `Health` reached zero
+#[derive(Message, Debug, Clone, Copy)]
+pub struct Died {
+    pub entity: Entity,
+    /// Source of the killing blow
+    pub killer: Option<Entity>,
+    pub kind: DamageKind,
+}
+
+/// How an entity with [`Health`] takes hits
+#[derive(Component, Debug, Clone)]
+pub struct Defense {
+    /// Flat amount taken off every hit, after resistances
+    pub armor: f32,
+    /// Fraction of each kind of damage ignored, from 0 to 1
+    pub resistances: Vec<(DamageKind, f32)>,
+    /// Seconds of invulnerability after taking damage
+    pub invulnerability: f32,
+    invulnerable_for: f32,
+}
+
+impl Defense {
+    pub fn new(armor: f32, resistances: Vec<(DamageKind, f32)>, invulnerability: f32) -> Self {
+        Self { armor, resistances, invulnerability, invulnerable_for: 0.0 }
+    }
+
+    /// Ends any invulnerability window still running
+    pub fn reset(&mut self) {
+        self.invulnerable_for = 0.0;
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_for > 0.0
+    }
+
+    /// Damage left of a hit of `amount` and `kind` after resistance and armor
+    pub fn mitigate(&self, amount: f32, kind: DamageKind) -> f32 {
+        let resistance = self
+            .resistances
+            .iter()
+            .find(|(resisted, _)| *resisted == kind)
+            .map_or(0.0, |(_, fraction)| fraction.clamp(0.0, 1.0));
+        (amount * (1.0 - resistance) - self.armor).max(0.0)
+    }
+}
+
+/// Applies this frame's damage to `Health`, honoring each target's [`Defense`],
+/// and reports deaths. Hits on dead or invulnerable targets are dropped; only
+/// hits that take health off the player count in [`RunStats`].
+pub(crate) fn apply_damage(
+    time: Res<Time>,
+    mut damage: MessageReader<DamageEvent>,
+    mut died: MessageWriter<Died>,
+    mut stats: ResMut<RunStats>,
+    mut target_query: Query<(&mut Health, Option<&mut Defense>, Has<CubeController>)>,
+) {
+    for (_, defense, _) in target_query.iter_mut() {
+        if let Some(mut defense) = defense {
+            if defense.invulnerable_for > 0.0 {
+                defense.invulnerable_for -= time.delta_secs();
+            }
+        }
+    }
+
+    for hit in damage.read() {
+        let Ok((mut health, mut defense, is_player)) = target_query.get_mut(hit.target) else {
+            continue;
+        };
+        if health.current <= 0.0 {
+            continue;
+        }
+        let amount = match defense.as_deref() {
+            Some(defense) if defense.is_invulnerable() => {
+                debug!("{} is invulnerable, ignoring {:?} damage", hit.target, hit.kind);
+                continue;
+            }
+            Some(defense) => defense.mitigate(hit.amount, hit.kind),
+            None => hit.amount,
+        };
+        if amount <= 0.0 {
+            continue;
+        }
+
+        health.current = (health.current - amount).max(0.0);
+        if is_player {
+            stats.hits_taken += 1;
+        }
+        if let Some(defense) = defense.as_deref_mut() {
+            defense.invulnerable_for = defense.invulnerability;
+        }
+        if health.current <= 0.0 {
+            died.write(Died { entity: hit.target, killer: hit.source, kind: hit.kind });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn mitigate_applies_resistance_before_armor() {
+        let defense = Defense::new(2.0, vec![(DamageKind::Contact, 0.5)], 0.0);
+        // (10 * 0.5) - 2, not (10 - 2) * 0.5
+        assert_eq!(defense.mitigate(10.0, DamageKind::Contact), 3.0);
+        assert_eq!(defense.mitigate(10.0, DamageKind::Explosion), 8.0);
+        // Armor never heals
+        assert_eq!(defense.mitigate(1.0, DamageKind::Explosion), 0.0);
+    }
+
+    #[test]
+    fn mitigate_clamps_resistances() {
+        let defense = Defense::new(0.0, vec![(DamageKind::Explosion, 1.5), (DamageKind::Environment, -1.0)], 0.0);
+        assert_eq!(defense.mitigate(10.0, DamageKind::Explosion), 0.0);
+        assert_eq!(defense.mitigate(10.0, DamageKind::Environment), 10.0);
+        assert_eq!(defense.mitigate(10.0, DamageKind::Contact), 10.0);
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<RunStats>()
+            .add_message::<DamageEvent>()
+            .add_message::<Died>()
+            .add_systems(Update, apply_damage);
+        app
+    }
+
+    fn hit(app: &mut App, target: Entity, amount: f32) {
+        app.world_mut().write_message(DamageEvent { target, amount, source: None, kind: DamageKind::Contact });
+        app.update();
+    }
+
+    fn health(app: &App, target: Entity) -> f32 {
+        app.world().get::<Health>(target).unwrap().current
+    }
+
+    #[test]
+    fn hits_during_invulnerability_are_ignored() {
+        let mut app = app();
+        let player = app
+            .world_mut()
+            .spawn((CubeController, Health { current: 100.0, max: 100.0 }, Defense::new(0.0, Vec::new(), 1.0)))
+            .id();
+
+        hit(&mut app, player, 10.0);
+        assert_eq!(health(&app, player), 90.0);
+        assert!(app.world().get::<Defense>(player).unwrap().is_invulnerable());
+
+        // Time stands still, so the window is still open
+        hit(&mut app, player, 10.0);
+        assert_eq!(health(&app, player), 90.0);
+        assert_eq!(app.world().resource::<RunStats>().hits_taken, 1);
+
+        app.world_mut().resource_mut::<Time>().advance_by(Duration::from_secs_f32(1.5));
+        hit(&mut app, player, 10.0);
+        assert_eq!(health(&app, player), 80.0);
+        assert_eq!(app.world().resource::<RunStats>().hits_taken, 2);
+    }
+
+    #[test]
+    fn only_player_hits_are_counted() {
+        let mut app = app();
+        let prop = app.world_mut().spawn(Health { current: 50.0, max: 50.0 }).id();
+        hit(&mut app, prop, 10.0);
+        assert_eq!(health(&app, prop), 40.0);
+        assert_eq!(app.world().resource::<RunStats>().hits_taken, 0);
+    }
+
+    #[derive(Resource, Default)]
+    struct Deaths(usize);
+
+    #[test]
+    fn lethal_hit_reports_death_once() {
+        let mut app = app();
+        app.init_resource::<Deaths>().add_systems(
+            Update,
+            (|mut died: MessageReader<Died>, mut deaths: ResMut<Deaths>| deaths.0 += died.read().count())
+                .after(apply_damage),
+        );
+        let player = app.world_mut().spawn((CubeController, Health { current: 5.0, max: 100.0 })).id();
+        hit(&mut app, player, 10.0);
+        hit(&mut app, player, 10.0);
+        assert_eq!(health(&app, player), 0.0);
+        assert_eq!(app.world().resource::<Deaths>().0, 1);
+    }
+}
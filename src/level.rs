@@ -0,0 +1,699 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::archetype::{ArchetypeRegistry, SpawnChoice};
+use crate::collision::{AutoCollider, AutoFit, ColliderShape, Ground, ObstacleBlocker};
+use crate::damage::{DamageKind, Defense};
+use crate::nav::NavGrid;
+use crate::radar::Radar;
+use crate::spawn::SpawnMarker;
+use crate::state::RunStartRng;
+use crate::waves::{wave_label, DifficultyCurve, Wave, WaveDirector};
+use crate::{
+    CameraAngle, CameraController, CubeController, Health, Level0Settings, RotatingRadar, WorldRng,
+};
+
+/// A level description loaded from a `.ron` file.
+///
+/// Describes everything `spawn_level` puts in the world: ground, sky, player start,
+/// camera, fog, lights, props and enemy waves.
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct LevelDescription {
+    pub ground: GroundDef,
+    #[serde(default)]
+    pub sky: Option<SkyDef>,
+    pub player: PlayerDef,
+    pub camera: CameraDef,
+    pub fog: FogDef,
+    pub ambient: AmbientDef,
+    #[serde(default)]
+    pub lights: Vec<LightDef>,
+    #[serde(default)]
+    pub props: Vec<PropDef>,
+    /// Invisible volumes that only block, such as the walls around the ground
+    #[serde(default)]
+    pub colliders: Vec<VolumeDef>,
+    /// Fallback enemy spawn points for when no random point around the player is valid
+    #[serde(default)]
+    pub spawn_points: Vec<[f32; 3]>,
+    /// Enemy waves; a level without them has no enemies
+    #[serde(default)]
+    pub waves: Option<WavesDef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GroundDef {
+    /// Side length of the square ground plane
+    pub size: f32,
+    pub texture: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SkyDef {
+    pub radius: f32,
+    pub texture: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlayerDef {
+    pub position: [f32; 3],
+    pub health: f32,
+    /// Flat damage taken off every hit
+    #[serde(default)]
+    pub armor: f32,
+    /// Fraction of each kind of damage ignored
+    #[serde(default)]
+    pub resistances: Vec<(DamageKind, f32)>,
+    /// Seconds of invulnerability after a hit
+    #[serde(default)]
+    pub invulnerability: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CameraDef {
+    pub position: [f32; 3],
+    pub look_at: [f32; 3],
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FogDef {
+    pub color: [f32; 3],
+    pub start: f32,
+    pub end: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AmbientDef {
+    pub color: [f32; 3],
+    pub brightness: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum LightDef {
+    Directional {
+        color: [f32; 3],
+        illuminance: f32,
+        shadows: bool,
+        /// Degrees around Y
+        yaw: f32,
+        /// Degrees around X
+        pitch: f32,
+    },
+    Point {
+        color: [f32; 3],
+        intensity: f32,
+        range: f32,
+        shadows: bool,
+        /// World position, or offset from the anchor prop when `anchor` is set
+        position: [f32; 3],
+        /// Name of a prop to place this light relative to
+        #[serde(default)]
+        anchor: Option<String>,
+    },
+}
+
+/// A group of identical props placed by one rule
+#[derive(Debug, Deserialize)]
+pub struct PropDef {
+    /// Lets lights anchor to the (first) placed instance
+    #[serde(default)]
+    pub name: Option<String>,
+    pub model: PropModel,
+    #[serde(default = "default_count")]
+    pub count: usize,
+    pub placement: Placement,
+    /// Uniform scale range `(min, max)`
+    pub scale: (f32, f32),
+    /// Base height
+    #[serde(default)]
+    pub y: f32,
+    /// Extra height per unit of scale, e.g. 0.5 sinks a unit sphere halfway into the ground
+    #[serde(default)]
+    pub y_from_scale: f32,
+    #[serde(default = "default_true")]
+    pub random_yaw: bool,
+    #[serde(default)]
+    pub tags: Vec<PropTag>,
+    #[serde(default)]
+    pub collider: Option<ColliderDef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub enum PropModel {
+    /// glTF scene path, e.g. `"models/glacier/Iceberg.gltf#Scene0"`
+    Scene(String),
+    /// Unit sphere mesh with a base color texture
+    Sphere { texture: String },
+}
+
+#[derive(Debug, Deserialize)]
+pub enum Placement {
+    /// Fixed ground position `(x, z)`
+    At(f32, f32),
+    /// Random point on a ring around the origin. Props with `spacing` keep at least
+    /// that distance from every other spaced prop placed before them.
+    Ring {
+        min_radius: f32,
+        max_radius: f32,
+        #[serde(default)]
+        spacing: Option<f32>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub enum PropTag {
+    RotatingRadar,
+    /// Detection cone along the prop's forward axis; `fov` is in degrees
+    Radar { range: f32, fov: f32, alert_radius: f32 },
+}
+
+/// The `ObstacleBlocker` of a prop
+#[derive(Debug, Deserialize)]
+pub enum ColliderDef {
+    /// Fixed shape centered on the prop, in its unscaled units
+    Shape(ShapeDef),
+    /// Fitted to the model's bounds once its glTF scene has spawned
+    Auto(AutoFit),
+}
+
+/// Serialized form of [`ColliderShape`]
+#[derive(Debug, Deserialize)]
+pub enum ShapeDef {
+    Box { half_size: (f32, f32, f32) },
+    Cylinder { radius: f32, half_height: f32 },
+    Sphere { radius: f32 },
+    Capsule { radius: f32, half_length: f32 },
+}
+
+impl From<&ShapeDef> for ColliderShape {
+    fn from(shape: &ShapeDef) -> Self {
+        match *shape {
+            ShapeDef::Box { half_size: (x, y, z) } => ColliderShape::Box { half_size: Vec3::new(x, y, z) },
+            ShapeDef::Cylinder { radius, half_height } => ColliderShape::Cylinder { radius, half_height },
+            ShapeDef::Sphere { radius } => ColliderShape::Sphere { radius },
+            ShapeDef::Capsule { radius, half_length } => ColliderShape::Capsule { radius, half_length },
+        }
+    }
+}
+
+/// A collision-only entity: a transform and a blocker, nothing to render
+#[derive(Debug, Deserialize)]
+pub struct VolumeDef {
+    pub shape: ShapeDef,
+    pub position: [f32; 3],
+    /// Degrees around Y
+    #[serde(default)]
+    pub yaw: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WavesDef {
+    /// Seconds before the first wave
+    pub first_break: f32,
+    /// Spawning pauses while this many enemies are alive
+    pub max_alive: usize,
+    /// Waves in order; the last one repeats once the schedule runs out
+    pub schedule: Vec<WaveDef>,
+    #[serde(default)]
+    pub difficulty: DifficultyCurve,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WaveDef {
+    /// Enemies in the wave at intensity 1
+    pub count: usize,
+    /// Archetype names from the archetype registry with their relative spawn weights
+    pub archetypes: Vec<(String, f32)>,
+    /// Seconds between spawns
+    pub spawn_interval: f32,
+    /// Seconds of quiet after the wave's last spawn
+    pub break_time: f32,
+    /// Spawn ring around the player
+    pub min_radius: f32,
+    pub max_radius: f32,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LevelLoaderError {
+    #[error("could not read level file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse level file: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = LevelDescription;
+    type Settings = ();
+    type Error = LevelLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Marks everything that belongs to the running level, so a restart can clear it
+#[derive(Component)]
+pub struct LevelEntity;
+
+/// The level being played and whether it has been spawned yet
+#[derive(Resource)]
+pub struct CurrentLevel {
+    pub handle: Handle<LevelDescription>,
+    /// Enemy archetypes the level's waves pick from
+    pub archetypes: Handle<ArchetypeRegistry>,
+    pub spawned: bool,
+}
+
+pub(crate) fn load_level(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Level0Settings>,
+) {
+    commands.insert_resource(CurrentLevel {
+        handle: asset_server.load(settings.level.clone()),
+        archetypes: asset_server.load(settings.archetypes.clone()),
+        spawned: false,
+    });
+}
+
+/// Run condition: the level and archetype files have loaded but nothing is spawned yet
+pub(crate) fn level_ready(
+    level: Option<Res<CurrentLevel>>,
+    levels: Res<Assets<LevelDescription>>,
+    registries: Res<Assets<ArchetypeRegistry>>,
+) -> bool {
+    level.is_some_and(|level| {
+        !level.spawned && levels.contains(&level.handle) && registries.contains(&level.archetypes)
+    })
+}
+
+fn rgb([r, g, b]: [f32; 3]) -> Color {
+    Color::srgb(r, g, b)
+}
+
+/// Spawns the world described by the current level
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn spawn_level(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    settings: Res<Level0Settings>,
+    mut rng: ResMut<WorldRng>,
+    mut level: ResMut<CurrentLevel>,
+    levels: Res<Assets<LevelDescription>>,
+    registries: Res<Assets<ArchetypeRegistry>>,
+) {
+    let (Some(desc), Some(registry)) = (levels.get(&level.handle), registries.get(&level.archetypes)) else {
+        return;
+    };
+    level.spawned = true;
+    info!("World seed: {}", rng.seed());
+
+    // Fresh navigation grid; blockers fill it in as they are indexed
+    commands.insert_resource(NavGrid::new(desc.ground.size));
+
+    // Large square base platform with texture
+    let half_ground = desc.ground.size * 0.5;
+    commands.spawn((
+        LevelEntity,
+        Mesh3d(meshes.add(Rectangle::new(desc.ground.size, desc.ground.size))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color_texture: Some(asset_server.load(&desc.ground.texture)),
+            ..default()
+        })),
+        Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+        // Large flat collision box for the ground; the plane's local Z is world up
+        ObstacleBlocker::cuboid(Vec3::new(half_ground, half_ground, 0.1)),
+        Ground,
+    ));
+
+    // Greyscale cube the player drives
+    commands.spawn((
+        LevelEntity,
+        Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.5, 0.5, 0.5), // Greyscale
+            emissive: Color::srgb(0.0, 0.0, 0.0).into(), // No emissive
+            alpha_mode: AlphaMode::Blend, // Enable transparency
+            ..default()
+        })),
+        Transform::from_translation(Vec3::from_array(desc.player.position)),
+        CubeController,
+        Health { current: desc.player.health, max: desc.player.health },
+        Defense::new(desc.player.armor, desc.player.resistances.clone(), desc.player.invulnerability),
+    ));
+
+    if let Some(sky) = &desc.sky {
+        commands.spawn((
+            LevelEntity,
+            Mesh3d(meshes.add(Sphere::new(sky.radius))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color_texture: Some(asset_server.load(&sky.texture)),
+                unlit: true, // Skybox should not be affected by lighting
+                ..default()
+            })),
+            Transform::from_scale(Vec3::splat(-1.0)), // Invert the sphere so we see the inside
+        ));
+    }
+
+    // Positions of props placed with spacing, for distance checking
+    let mut spaced_positions: Vec<Vec2> = Vec::new();
+    // First placed position of every named prop, for anchoring lights
+    let mut anchors: Vec<(&str, Vec3)> = Vec::new();
+
+    for prop in &desc.props {
+        for _ in 0..prop.count {
+            let ground_pos = place_prop(&prop.placement, &mut rng, &mut spaced_positions);
+            let scale = rng.range(prop.scale.0, prop.scale.1);
+            let yaw = if prop.random_yaw { rng.angle() } else { 0.0 };
+            let rotation = Quat::from_rotation_y(yaw);
+            let translation = Vec3::new(ground_pos.x, prop.y + prop.y_from_scale * scale, ground_pos.y);
+
+            if let Some(name) = &prop.name {
+                if !anchors.iter().any(|(anchor, _)| anchor == name) {
+                    anchors.push((name, translation));
+                }
+            }
+
+            let transform = Transform::from_scale(Vec3::splat(scale))
+                .with_translation(translation)
+                .with_rotation(rotation);
+            let entity = match &prop.model {
+                PropModel::Scene(path) => commands.spawn((LevelEntity, SceneRoot(asset_server.load(path)), transform)),
+                PropModel::Sphere { texture } => commands.spawn((
+                    LevelEntity,
+                    Mesh3d(meshes.add(Sphere::new(1.0))),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color_texture: Some(asset_server.load(texture)),
+                        ..default()
+                    })),
+                    transform,
+                )),
+            }
+            .id();
+            for tag in &prop.tags {
+                match tag {
+                    PropTag::RotatingRadar => commands.entity(entity).insert(RotatingRadar),
+                    PropTag::Radar { range, fov, alert_radius } => {
+                        commands.entity(entity).insert(Radar::new(*range, fov.to_radians(), *alert_radius))
+                    }
+                };
+            }
+
+            match &prop.collider {
+                Some(ColliderDef::Shape(shape)) => {
+                    commands.entity(entity).insert(ObstacleBlocker::new(ColliderShape::from(shape)));
+                }
+                Some(ColliderDef::Auto(fit)) => {
+                    commands.entity(entity).insert(AutoCollider { fit: *fit });
+                }
+                None => {}
+            }
+        }
+    }
+
+    for volume in &desc.colliders {
+        commands.spawn((
+            LevelEntity,
+            Transform::from_translation(Vec3::from_array(volume.position))
+                .with_rotation(Quat::from_rotation_y(volume.yaw.to_radians())),
+            ObstacleBlocker::new(ColliderShape::from(&volume.shape)),
+        ));
+    }
+
+    for light in &desc.lights {
+        match light {
+            LightDef::Directional { color, illuminance, shadows, yaw, pitch } => {
+                commands.spawn((
+                    LevelEntity,
+                    DirectionalLight {
+                        color: rgb(*color),
+                        illuminance: *illuminance,
+                        shadows_enabled: *shadows,
+                        ..default()
+                    },
+                    Transform::from_rotation(Quat::from_euler(
+                        EulerRot::ZYX,
+                        0.0,
+                        yaw.to_radians(),
+                        pitch.to_radians(),
+                    )),
+                ));
+            }
+            LightDef::Point { color, intensity, range, shadows, position, anchor } => {
+                let offset = match anchor {
+                    Some(name) => match anchors.iter().find(|(anchor, _)| anchor == name) {
+                        Some((_, anchor_pos)) => Vec3::new(anchor_pos.x, 0.0, anchor_pos.z),
+                        None => {
+                            warn!("Light anchor `{}` does not name a prop", name);
+                            Vec3::ZERO
+                        }
+                    },
+                    None => Vec3::ZERO,
+                };
+                commands.spawn((
+                    LevelEntity,
+                    PointLight {
+                        color: rgb(*color),
+                        intensity: *intensity,
+                        range: *range,
+                        shadows_enabled: *shadows,
+                        ..default()
+                    },
+                    Transform::from_translation(offset + Vec3::from_array(*position)),
+                ));
+            }
+        }
+    }
+
+    commands.spawn((
+        LevelEntity,
+        AmbientLight {
+            color: rgb(desc.ambient.color),
+            brightness: desc.ambient.brightness,
+            affects_lightmapped_meshes: true,
+        },
+    ));
+
+    // Main camera, repositioned every frame by follow_camera
+    commands.spawn((
+        LevelEntity,
+        Camera3d::default(),
+        DistanceFog {
+            color: rgb(desc.fog.color),
+            falloff: FogFalloff::Linear {
+                start: settings.fog_start.unwrap_or(desc.fog.start),
+                end: settings.fog_end.unwrap_or(desc.fog.end),
+            },
+            ..default()
+        },
+        Transform::from_translation(Vec3::from_array(desc.camera.position))
+            .looking_at(Vec3::from_array(desc.camera.look_at), Vec3::Y),
+        CameraController,
+        CameraAngle { pitch: 0.0 },
+    ));
+
+    for position in &desc.spawn_points {
+        commands.spawn((LevelEntity, SpawnMarker, Transform::from_translation(Vec3::from_array(*position))));
+    }
+
+    // Wave number in the HUD, and health where the web page does not show it
+    commands.spawn((LevelEntity, wave_label()));
+    #[cfg(not(target_arch = "wasm32"))]
+    commands.spawn((LevelEntity, crate::health_label()));
+    // Restarts replay the waves from here
+    commands.insert_resource(RunStartRng(rng.clone()));
+
+    let Some(waves) = &desc.waves else {
+        return;
+    };
+    let schedule: Vec<Wave> = waves
+        .schedule
+        .iter()
+        .enumerate()
+        .filter_map(|(index, wave)| {
+            let choices = resolve_archetypes(&wave.archetypes, registry, &asset_server);
+            if !choices.iter().any(|choice| choice.weight > 0.0) {
+                warn!("Wave {} has no archetypes it can spawn; skipping it", index + 1);
+                return None;
+            }
+            Some(Wave {
+                count: wave.count,
+                choices,
+                spawn_interval: settings.spawn_interval.unwrap_or(wave.spawn_interval),
+                break_time: wave.break_time,
+                min_radius: wave.min_radius,
+                max_radius: wave.max_radius,
+            })
+        })
+        .collect();
+    if schedule.is_empty() {
+        warn!("Level has no waves that can spawn anything");
+        return;
+    }
+    commands.spawn((
+        LevelEntity,
+        WaveDirector::new(
+            schedule,
+            waves.difficulty,
+            settings.max_ugvs.unwrap_or(waves.max_alive),
+            waves.first_break,
+        ),
+    ));
+}
+
+/// Looks up weighted archetype names in the registry, skipping unknown ones
+fn resolve_archetypes(
+    names: &[(String, f32)],
+    registry: &ArchetypeRegistry,
+    asset_server: &AssetServer,
+) -> Vec<SpawnChoice> {
+    names
+        .iter()
+        .filter_map(|(name, weight)| {
+            let Some(archetype) = registry.archetypes.get(name) else {
+                warn!("Wave refers to unknown enemy archetype {:?}", name);
+                return None;
+            };
+            Some(SpawnChoice {
+                name: name.clone(),
+                weight: *weight,
+                archetype: archetype.clone(),
+                model: asset_server.load(&archetype.model),
+            })
+        })
+        .collect()
+}
+
+/// Picks a ground position `(x, z)` for one prop instance
+fn place_prop(placement: &Placement, rng: &mut WorldRng, spaced_positions: &mut Vec<Vec2>) -> Vec2 {
+    let (min_radius, max_radius, spacing) = match *placement {
+        Placement::At(x, z) => return Vec2::new(x, z),
+        Placement::Ring { min_radius, max_radius, spacing } => (min_radius, max_radius, spacing),
+    };
+
+    let mut candidate = Vec2::ZERO;
+    for _ in 0..50 {
+        let angle = rng.angle();
+        let radius = rng.range(min_radius, max_radius);
+        candidate = Vec2::new(angle.cos(), angle.sin()) * radius;
+
+        let Some(spacing) = spacing else {
+            return candidate;
+        };
+        if spaced_positions.iter().all(|pos| pos.distance(candidate) >= spacing) {
+            spaced_positions.push(candidate);
+            return candidate;
+        }
+    }
+
+    warn!("No well-spaced position found for a prop, placing it at {:?}", candidate);
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archetype::ArchetypeRegistry;
+
+    fn level0() -> LevelDescription {
+        ron::from_str(include_str!("../assets/levels/level0.ron")).unwrap()
+    }
+
+    #[test]
+    fn level0_parses() {
+        let level = level0();
+        let half_ground = level.ground.size * 0.5;
+        assert!(level.player.health > 0.0);
+        assert!(!level.props.is_empty());
+        assert_eq!(level.colliders.len(), 4);
+        for point in &level.spawn_points {
+            assert!(point[0].abs() < half_ground && point[2].abs() < half_ground, "{point:?} is off the ground");
+        }
+        let shapes: Vec<ColliderShape> = level
+            .props
+            .iter()
+            .filter_map(|prop| match &prop.collider {
+                Some(ColliderDef::Shape(shape)) => Some(shape.into()),
+                _ => None,
+            })
+            .collect();
+        assert!(shapes.contains(&ColliderShape::Sphere { radius: 1.0 }));
+    }
+
+    #[test]
+    fn level0_walls_close_off_the_ground() {
+        let level = level0();
+        let half_ground = level.ground.size * 0.5;
+        for wall in &level.colliders {
+            let ColliderShape::Box { half_size } = ColliderShape::from(&wall.shape) else {
+                panic!("{:?} is not a box", wall.shape);
+            };
+            // Each wall spans a whole side, just outside the ground's edge
+            let position = Vec3::from_array(wall.position);
+            let (across, along) = if half_size.x < half_size.z { (0, 2) } else { (2, 0) };
+            assert!(half_size[along] >= half_ground, "{wall:?}");
+            assert!(position[across].abs() - half_size[across] >= half_ground, "{wall:?}");
+        }
+    }
+
+    #[test]
+    fn level0_waves_use_shipped_archetypes() {
+        let level = level0();
+        let registry: ArchetypeRegistry =
+            ron::from_str(include_str!("../assets/enemies/default.archetypes.ron")).unwrap();
+        let waves = level.waves.expect("level0 has waves");
+        assert!(!waves.schedule.is_empty());
+        for wave in &waves.schedule {
+            assert!(wave.count > 0 && wave.min_radius < wave.max_radius, "{wave:?}");
+            for (name, weight) in &wave.archetypes {
+                assert!(registry.archetypes.contains_key(name), "unknown archetype {name:?}");
+                assert!(*weight >= 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn shape_defs_convert_to_collider_shapes() {
+        let parse = |text: &str| ColliderShape::from(&ron::from_str::<ShapeDef>(text).unwrap());
+        assert_eq!(
+            parse("Box(half_size: (1.0, 2.0, 3.0))"),
+            ColliderShape::Box { half_size: Vec3::new(1.0, 2.0, 3.0) }
+        );
+        assert_eq!(
+            parse("Cylinder(radius: 0.5, half_height: 2.0)"),
+            ColliderShape::Cylinder { radius: 0.5, half_height: 2.0 }
+        );
+        assert_eq!(parse("Sphere(radius: 1.5)"), ColliderShape::Sphere { radius: 1.5 });
+        assert_eq!(
+            parse("Capsule(radius: 0.4, half_length: 1.0)"),
+            ColliderShape::Capsule { radius: 0.4, half_length: 1.0 }
+        );
+        assert!(ron::from_str::<ShapeDef>("Cone(radius: 1.0)").is_err());
+    }
+}
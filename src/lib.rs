@@ -1,449 +1,170 @@
 use bevy::prelude::*;
-use wasm_bindgen::prelude::*;
 
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
 #[cfg(target_arch = "wasm32")]
 use web_sys;
 #[cfg(target_arch = "wasm32")]
 use js_sys;
 
-// Simple pseudo-random function for WASM compatibility
-fn pseudo_random(seed: f32) -> f32 {
-    let x = seed.sin() * 43758.5453;
-    x - x.floor()
+pub mod ai;
+pub mod archetype;
+pub mod collision;
+pub mod damage;
+pub mod level;
+pub mod nav;
+pub mod noise;
+pub mod pause;
+pub mod radar;
+mod rng;
+pub mod save;
+pub mod spatial;
+pub mod spawn;
+pub mod steering;
+pub mod vehicle;
+pub mod state;
+pub mod waves;
+
+use ai::{Behavior, BehaviorSpeeds, Intent, UgvBrain};
+use archetype::{ArchetypeDef, ArchetypeRegistry, DeathEffect, Enemy};
+use bevy::input::common_conditions::input_just_pressed;
+use collision::Obstacles;
+use damage::{DamageEvent, DamageKind};
+use level::{LevelDescription, LevelEntity};
+use nav::{NavGrid, NavPath};
+use spawn::SpawnValidator;
+use steering::Steering;
+use vehicle::Vehicle;
+pub use rng::WorldRng;
+use state::{GameState, RunStats};
+use waves::WaveDirector;
+
+/// Tunables for the level, inserted as a resource by [`Level0Plugin`]
+#[derive(Resource, Clone, Debug)]
+pub struct Level0Settings {
+    /// Seed for [`WorldRng`]; the same seed replays the same world and spawns
+    pub seed: u64,
+    /// Level file to load, relative to the asset root
+    pub level: String,
+    /// Enemy archetype file, relative to the asset root
+    pub archetypes: String,
+    /// Seconds between UGV spawns within a wave; `None` uses the level file
+    pub spawn_interval: Option<f32>,
+    /// Spawning pauses while this many UGVs are alive; `None` uses the level file
+    pub max_ugvs: Option<usize>,
+    /// Distance at which the fog starts; `None` uses the level file
+    pub fog_start: Option<f32>,
+    /// Distance at which the fog is fully opaque; `None` uses the level file
+    pub fog_end: Option<f32>,
+    /// Where F5 saves and F9 loads: a file path on native, a localStorage key on the web
+    pub save_slot: String,
+    /// Draw the player wireframe and UGV line-of-sight gizmos
+    pub debug_gizmos: bool,
+    /// Draw every collider as a wireframe; toggled in game with F3
+    pub debug_colliders: bool,
 }
 
-#[wasm_bindgen::prelude::wasm_bindgen(start)]
-pub fn main() {
-    App::new()
-        // Removed shadow map for better performance
-        .add_plugins(DefaultPlugins)
-        .insert_resource(ClearColor(Color::srgb(0.005, 0.005, 0.005))) // Much darker background
-        .add_systems(Startup, setup)
-        .add_systems(Update, (move_cube, follow_camera, spawn_footsteps, update_footsteps, draw_wireframe, rotate_radar, spawn_spheres, chase_cube, draw_line_of_sight, despawn_spheres, update_smoke, update_health))
-        .run();
+impl Default for Level0Settings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            level: "levels/level0.ron".to_string(),
+            archetypes: "enemies/default.archetypes.ron".to_string(),
+            spawn_interval: None,
+            max_ugvs: None,
+            fog_start: None,
+            fog_end: None,
+            save_slot: "level0.save.ron".to_string(),
+            debug_gizmos: true,
+            debug_colliders: false,
+        }
+    }
 }
 
-/// set up a simple 3D scene with a single centered cube and circuit-textured base
-fn setup(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
-) {
-    // Large square base platform with texture
-    commands.spawn((
-        Mesh3d(meshes.add(Rectangle::new(400.0, 400.0))), // 2x smaller
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color_texture: Some(asset_server.load("textures/snow_02_diff_4k.png")),
-            ..default()
-        })),
-        Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        ObstacleBlocker {
-            half_size: Vec3::new(200.0, 0.1, 200.0), // Large flat collision box for the ground
-        },
-    ));
-    
-    // Greyscale cube floating above the base
-    commands.spawn((
-        Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgb(0.5, 0.5, 0.5), // Greyscale
-            emissive: Color::srgb(0.0, 0.0, 0.0).into(), // No emissive
-            alpha_mode: AlphaMode::Blend, // Enable transparency
-            ..default()
-        })),
-        Transform::from_xyz(0.0, 0.5, 0.0), // Half unit above the base
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        CubeController, // Add controller component
-        Health { current: 100.0, max: 100.0 }, // Add health component
-    ));
+/// The whole Level 0 game: world setup, player, camera, UGVs and HUD sync.
+///
+/// Does not add `DefaultPlugins`, so it can be embedded in any Bevy app.
+#[derive(Default)]
+pub struct Level0Plugin {
+    pub settings: Level0Settings,
+}
 
-    
-    // Add directional light for better visibility
-    commands.spawn((
-        DirectionalLight {
-            color: Color::srgb(1.0, 1.0, 1.0), // Bright white
-            illuminance: 20000.0, // Much more intense
-            shadows_enabled: true, // Enable shadows
-            ..default()
-        },
-        Transform::from_rotation(Quat::from_euler(
-            EulerRot::ZYX,
-            0.0,
-            0.0_f32.to_radians(), // More overhead
-            -60.0_f32.to_radians(), // Steeper angle
-        )),
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-    ));
-    
-    // Create skybox with stars texture
-    commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(1000.0))), // Large sphere for skybox
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color_texture: Some(asset_server.load("textures/stars.png")),
-            unlit: true, // Skybox should not be affected by lighting
-            ..default()
-        })),
-        Transform::from_scale(Vec3::splat(-1.0)), // Invert the sphere so we see the inside
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-    ));
-
-    // Add industrial building in the distance
-    let building_angle = pseudo_random(300.0) * 2.0 * std::f32::consts::PI;
-    let building_radius = pseudo_random(301.0) * 80.0 + 100.0; // Far distance: 100-180 units
-    let building_x = building_angle.cos() * building_radius;
-    let building_z = building_angle.sin() * building_radius;
-    let building_scale = pseudo_random(302.0) * 0.1 + 0.05; // Small scale for distant building
-    let building_rotation = pseudo_random(303.0) * 2.0 * std::f32::consts::PI;
-    
-    // Spawn the building GLB model
-    commands.spawn((
-        SceneRoot(asset_server.load("models/building/industrialbuildingpart.gltf#Scene0")),
-        Transform::from_scale(Vec3::splat(building_scale))
-            .with_translation(Vec3::new(building_x, 0.0, building_z))
-            .with_rotation(Quat::from_rotation_y(building_rotation)),
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        ObstacleBlocker {
-            half_size: Vec3::new(10.0, 7.5, 10.0), // Building collision box half-size
-        },
-    ));
-    
-    // Add invisible collision mesh for the building
-    commands.spawn((
-        Mesh3d(meshes.add(Cuboid::new(20.0, 15.0, 20.0))), // Invisible collision box
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgba(0.0, 0.0, 0.0, 0.0), // Completely transparent
-            alpha_mode: AlphaMode::Mask(0.5), // Use mask mode for reliable transparency
-            unlit: true, // Don't affect lighting
-            ..default()
-        })),
-        Transform::from_scale(Vec3::splat(building_scale))
-            .with_translation(Vec3::new(building_x, 0.0, building_z)) // Position at ground level
-            .with_rotation(Quat::from_rotation_y(building_rotation)),
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        ObstacleBlocker {
-            half_size: Vec3::new(10.0, 5.0, 10.0), // Building collision box half-size (10 units tall)
-        },
-    ));
-    
-    // Add red overhead light illuminating the building
-    commands.spawn((
-        PointLight {
-            color: Color::srgb(1.0, 0.0, 0.0), // Bright red
-            intensity: 100000.0, // Much brighter
-            range: 100.0, // Much wider range to illuminate building
-            shadows_enabled: true,
-            ..default()
-        },
-        Transform::from_xyz(building_x, 390.0, building_z), // Higher above the building
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-    ));
-    
-    // Track all object positions for distance checking
-    let mut all_positions = Vec::new();
-    
-    // Spawn all 11 glaciers (including the first one) in a consolidated loop
-    for i in 0..11 {
-        let mut attempts = 0;
-        let mut valid_position = false;
-        let mut glacier_x = 0.0;
-        let mut glacier_z = 0.0;
-        
-        // For the first glacier (i=0), place it directly without distance checking
-        if i == 0 {
-            let glacier_angle = pseudo_random(400.0) * 2.0 * std::f32::consts::PI;
-            let glacier_radius = pseudo_random(401.0) * 80.0 + 100.0; // Far distance: 100-180 units
-            glacier_x = glacier_angle.cos() * glacier_radius;
-            glacier_z = glacier_angle.sin() * glacier_radius;
-            valid_position = true;
-            all_positions.push((glacier_x, glacier_z));
-        } else {
-            // For subsequent glaciers, try to find a position that's not too close to existing objects
-            while !valid_position && attempts < 50 {
-                let glacier_angle = pseudo_random(500.0 + i as f32 + attempts as f32) * 2.0 * std::f32::consts::PI;
-                let glacier_radius = pseudo_random(501.0 + i as f32 + attempts as f32) * 80.0 + 100.0; // Far distance: 100-180 units
-                glacier_x = glacier_angle.cos() * glacier_radius;
-                glacier_z = glacier_angle.sin() * glacier_radius;
-                
-                // Check distance from all existing object positions
-                let mut too_close = false;
-                for (existing_x, existing_z) in &all_positions {
-                    let distance = ((glacier_x - existing_x).powi(2) + (glacier_z - existing_z).powi(2)).sqrt();
-                    if distance < 25.0 { // Minimum distance of 25 units between objects
-                        too_close = true;
-                        break;
-                    }
-                }
-                
-                if !too_close {
-                    valid_position = true;
-                    all_positions.push((glacier_x, glacier_z));
-                }
-                
-                attempts += 1;
-            }
-        }
-        
-        let glacier_scale = if i == 0 {
-            pseudo_random(402.0) * 0.08 + 0.05 // Original scale for first glacier
-        } else {
-            pseudo_random(502.0 + i as f32) * 0.08 + 0.05 // Same small scale: 0.05-0.13
-        };
-        let glacier_rotation = if i == 0 {
-            pseudo_random(403.0) * 2.0 * std::f32::consts::PI // Original rotation for first glacier
-        } else {
-            pseudo_random(503.0 + i as f32) * 2.0 * std::f32::consts::PI
-        };
-        let glacier_height = -4.0; // Same low height
-        
-        // Spawn the glacier GLB model
-        commands.spawn((
-            SceneRoot(asset_server.load("models/glacier/Iceberg.gltf#Scene0")),
-            Transform::from_scale(Vec3::splat(glacier_scale))
-                .with_translation(Vec3::new(glacier_x, glacier_height, glacier_z))
-                .with_rotation(Quat::from_rotation_y(glacier_rotation)),
-            GlobalTransform::default(),
-            Visibility::default(),
-            InheritedVisibility::default(),
-            ObstacleBlocker {
-            half_size: Vec3::new(10.0, 7.5, 10.0), // Building collision box half-size
-        },
-        )).id();
-        
-         // Add visible collision mesh for the glacier
-         commands.spawn((
-             Mesh3d(meshes.add(Cylinder::new(160.0, 80.0))), // Disc-shaped collision mesh
-             MeshMaterial3d(materials.add(StandardMaterial {
-                 base_color: Color::srgba(0.0, 0.0, 0.0, 0.0), // Completely transparent
-                 alpha_mode: AlphaMode::Mask(0.5), // Use mask mode for reliable transparency
-                 unlit: true, // Don't affect lighting
-                 ..default()
-             })),
-             Transform::from_scale(Vec3::splat(glacier_scale))
-                 .with_translation(Vec3::new(glacier_x, 0.0, glacier_z)) // Position at ground level
-                 .with_rotation(Quat::from_rotation_y(glacier_rotation)),
-             GlobalTransform::default(),
-             Visibility::default(),
-             InheritedVisibility::default(),
-             ObstacleBlocker {
-                 half_size: Vec3::new(80.0, 40.0, 80.0), // 10x larger glacier collision box half-size (80 units tall)
-             },
-         ));
-    }
-    
-    // Add radar model at random location with distance checking
-    let mut attempts = 0;
-    let mut valid_position = false;
-    let mut radar_x = 0.0;
-    let mut radar_z = 0.0;
-    
-    // Try to find a position that's not too close to existing objects
-    while !valid_position && attempts < 50 {
-        let radar_angle = pseudo_random(999.0 + attempts as f32) * 2.0 * std::f32::consts::PI;
-        let radar_radius = pseudo_random(998.0 + attempts as f32) * 20.0 + 150.0; // Closer to outskirts: 150-170
-        radar_x = radar_angle.cos() * radar_radius;
-        radar_z = radar_angle.sin() * radar_radius;
-        
-        // Check distance from all existing object positions
-        let mut too_close = false;
-        for (existing_x, existing_z) in &all_positions {
-            let distance = ((radar_x - existing_x).powi(2) + (radar_z - existing_z).powi(2)).sqrt();
-            if distance < 25.0 { // Minimum distance of 25 units between objects
-                too_close = true;
-                break;
-            }
-        }
-        
-        if !too_close {
-            valid_position = true;
-            all_positions.push((radar_x, radar_z));
-        }
-        
-        attempts += 1;
+impl Plugin for Level0Plugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .insert_resource(WorldRng::new(self.settings.seed))
+            .insert_resource(ClearColor(Color::srgb(0.005, 0.005, 0.005))) // Much darker background
+            .init_asset::<LevelDescription>()
+            .init_asset_loader::<level::LevelLoader>()
+            .init_asset::<ArchetypeRegistry>()
+            .init_asset_loader::<archetype::ArchetypeLoader>()
+            .add_observer(collision::fit_auto_collider)
+            .init_resource::<spatial::ObstacleIndex>()
+            .init_resource::<NavGrid>()
+            .add_message::<ai::Perceived>()
+            .add_message::<radar::RadarAlert>()
+            .add_message::<noise::Noise>()
+            .add_message::<DamageEvent>()
+            .add_message::<damage::Died>()
+            .add_systems(PreUpdate, (spatial::sync_obstacle_index, nav::sync_nav_grid).chain())
+            .init_state::<GameState>()
+            .init_resource::<RunStats>()
+            .add_systems(Startup, level::load_level)
+            .add_systems(Update, level::spawn_level.run_if(level::level_ready))
+            .add_systems(Update, state::finish_loading.run_if(in_state(GameState::Loading)))
+            .add_systems(OnEnter(GameState::MainMenu), state::spawn_main_menu)
+            .add_systems(Update, state::start_game.run_if(in_state(GameState::MainMenu).and(input_just_pressed(KeyCode::Enter))))
+            .add_systems(Update, (move_cube, follow_camera, spawn_footsteps, update_footsteps, rotate_radar, spawn_spheres, (noise::emit_player_noise, ai::see_player, radar::sweep_radars, noise::hear_noises, radar::raise_alarms, ai::deliver_perceptions, chase_cube).chain(), (despawn_spheres, damage::apply_damage, kill_player, state::check_game_over).chain(), state::tick_run_stats).run_if(in_state(GameState::Playing)))
+            // Death effects keep playing behind the game over screen
+            .add_systems(Update, update_smoke.run_if(in_state(GameState::Playing).or(in_state(GameState::GameOver))))
+            .add_systems(OnEnter(GameState::GameOver), state::spawn_game_over_screen)
+            .add_systems(Update, state::restart.run_if(in_state(GameState::GameOver).and(input_just_pressed(KeyCode::KeyR))))
+            .init_resource::<pause::PauseMenu>()
+            .add_systems(Update, pause::pause_on_key.run_if(in_state(GameState::Playing)))
+            .add_systems(PreUpdate, pause::pause_on_focus_loss.run_if(in_state(GameState::Playing)))
+            .add_systems(OnEnter(GameState::Paused), pause::enter_pause)
+            .add_systems(OnExit(GameState::Paused), pause::exit_pause)
+            .add_systems(Update, (pause::pause_menu_input, pause::update_pause_overlay).chain().run_if(in_state(GameState::Paused)))
+            .add_systems(Update, state::restart.run_if(in_state(GameState::Paused).and(resource_equals(pause::PauseMenu::Main)).and(input_just_pressed(KeyCode::KeyR))))
+            .add_systems(Update, save::save_game.run_if(in_state(GameState::Playing).or(in_state(GameState::Paused)).and(input_just_pressed(KeyCode::F5))))
+            .add_systems(Update, save::load_game.run_if(not(in_state(GameState::Loading)).and(input_just_pressed(KeyCode::F9))))
+            .add_systems(Update, save::apply_pending_load.run_if(resource_exists::<save::PendingLoad>))
+            .add_systems(Update, (update_health, waves::update_wave_label))
+            .add_systems(Update, (draw_wireframe, draw_line_of_sight, nav::draw_paths, radar::draw_radar_cones, spawn::draw_spawn_markers).run_if(debug_gizmos_enabled))
+            .add_systems(Update, toggle_debug_colliders.run_if(input_just_pressed(KeyCode::F3)))
+            .add_systems(Update, collision::draw_colliders.run_if(debug_colliders_enabled));
+
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(Startup, pause::watch_page_visibility)
+            .add_systems(PreUpdate, pause::pause_when_hidden);
     }
-    let radar_scale = pseudo_random(997.0) * 0.015 + 0.01; // Random scale between 0.01-0.025
-    let radar_rotation = pseudo_random(996.0) * 2.0 * std::f32::consts::PI;
-    
-    // Spawn the first radar GLB model
-    commands.spawn((
-        SceneRoot(asset_server.load("models/radar/Radar_HENSOLDT_ASR_NG.gltf#Scene0")),
-        Transform::from_scale(Vec3::splat(radar_scale))
-            .with_translation(Vec3::new(radar_x, 0.0, radar_z))
-            .with_rotation(Quat::from_rotation_y(radar_rotation)),
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        RotatingRadar, // Add rotating component
-        ObstacleBlocker {
-            half_size: Vec3::new(10.0, 7.5, 10.0), // Building collision box half-size
-        },
-    )).id();
-    
-    // Add invisible collision mesh for the radar
-    commands.spawn((
-        Mesh3d(meshes.add(Cuboid::new(3.0, 4.0, 3.0))), // Invisible collision box
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::srgba(0.0, 0.0, 0.0, 0.0), // Completely transparent
-            alpha_mode: AlphaMode::Mask(0.5), // Use mask mode for reliable transparency
-            unlit: true, // Don't affect lighting
-            ..default()
-        })),
-        Transform::from_scale(Vec3::splat(radar_scale))
-            .with_translation(Vec3::new(radar_x, 0.0, radar_z)) // Position at ground level
-            .with_rotation(Quat::from_rotation_y(radar_rotation)),
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        ObstacleBlocker {
-            half_size: Vec3::new(1.5, 1.0, 1.5), // Radar collision box half-size (2 units tall)
-        },
-    ));
+}
 
-    // Add 5 more HENSOLDT radars at random positions (avoiding glacier positions and each other)
-    let mut radar_positions = Vec::new();
-    radar_positions.push((radar_x, radar_z)); // Add the first radar position
-    
-    for i in 0..5 {
-        let mut attempts = 0;
-        let mut valid_position = false;
-        let mut radar_x = 0.0;
-        let mut radar_z = 0.0;
-        
-        // Try to find a position that's not too close to existing radars
-        while !valid_position && attempts < 50 {
-            let radar_angle = pseudo_random(990.0 + i as f32 + attempts as f32) * 2.0 * std::f32::consts::PI;
-            let radar_radius = pseudo_random(989.0 + i as f32 + attempts as f32) * 20.0 + 150.0; // Closer to outskirts: 150-170
-            radar_x = radar_angle.cos() * radar_radius;
-            radar_z = radar_angle.sin() * radar_radius;
-            
-            // Check distance from all existing object positions
-            let mut too_close = false;
-            for (existing_x, existing_z) in &all_positions {
-                let distance = ((radar_x - existing_x).powi(2) + (radar_z - existing_z).powi(2)).sqrt();
-                if distance < 25.0 { // Minimum distance of 25 units between objects
-                    too_close = true;
-                    break;
-                }
-            }
-            
-            if !too_close {
-                valid_position = true;
-                radar_positions.push((radar_x, radar_z));
-                all_positions.push((radar_x, radar_z));
-            }
-            
-            attempts += 1;
-        }
-        
-        let radar_scale = pseudo_random(988.0 + i as f32) * 0.015 + 0.01; // Random scale between 0.01-0.025 (2x smaller)
-        let radar_rotation = pseudo_random(987.0 + i as f32) * 2.0 * std::f32::consts::PI;
-        
-        // Spawn the radar GLB model
-        commands.spawn((
-            SceneRoot(asset_server.load("models/radar/Radar_HENSOLDT_ASR_NG.gltf#Scene0")),
-            Transform::from_scale(Vec3::splat(radar_scale))
-                .with_translation(Vec3::new(radar_x, 0.0, radar_z))
-                .with_rotation(Quat::from_rotation_y(radar_rotation)),
-            GlobalTransform::default(),
-            Visibility::default(),
-            InheritedVisibility::default(),
-            RotatingRadar, // Add rotating component
-            ObstacleBlocker {
-            half_size: Vec3::new(10.0, 7.5, 10.0), // Building collision box half-size
-        },
-        )).id();
-        
-        // Add invisible collision mesh for the radar
-        commands.spawn((
-            Mesh3d(meshes.add(Cuboid::new(3.0, 4.0, 3.0))), // Invisible collision box
-            MeshMaterial3d(materials.add(StandardMaterial {
-                base_color: Color::srgba(0.0, 0.0, 0.0, 0.0), // Completely transparent
-                alpha_mode: AlphaMode::Mask(0.5), // Use mask mode for reliable transparency
-                unlit: true, // Don't affect lighting
+fn debug_gizmos_enabled(settings: Res<Level0Settings>) -> bool {
+    settings.debug_gizmos
+}
+
+fn debug_colliders_enabled(settings: Res<Level0Settings>) -> bool {
+    settings.debug_colliders
+}
+
+fn toggle_debug_colliders(mut settings: ResMut<Level0Settings>) {
+    settings.debug_colliders = !settings.debug_colliders;
+}
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(start)]
+pub fn main() {
+    App::new()
+        // Removed shadow map for better performance
+        .add_plugins(DefaultPlugins)
+        .add_plugins(Level0Plugin {
+            settings: Level0Settings {
+                seed: rng::seed_from_url().unwrap_or_default(),
                 ..default()
-            })),
-            Transform::from_scale(Vec3::splat(radar_scale))
-                .with_translation(Vec3::new(radar_x, 0.0, radar_z)) // Position at ground level
-                .with_rotation(Quat::from_rotation_y(radar_rotation)),
-            GlobalTransform::default(),
-            Visibility::default(),
-            InheritedVisibility::default(),
-            ObstacleBlocker {
-                half_size: Vec3::new(1.5, 1.0, 1.5), // Radar collision box half-size (2 units tall)
-            },
-        ));
-    }
-    
-    // Add large rusty metal sphere at random location intersecting the plane
-    let sphere_angle = pseudo_random(995.0) * 2.0 * std::f32::consts::PI;
-    let sphere_radius = pseudo_random(994.0) * 30.0 + 20.0; // Random radius between 20-50
-    let sphere_x = sphere_angle.cos() * sphere_radius;
-    let sphere_z = sphere_angle.sin() * sphere_radius;
-    let sphere_scale = pseudo_random(993.0) * 4.0 + 6.0; // Random scale between 6-10 (half size)
-    
-    commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(sphere_scale))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color_texture: Some(asset_server.load("textures/rusty_metal_grid_diff_4k.png")),
-            ..default()
-        })),
-        Transform::from_xyz(sphere_x, sphere_scale * 0.5, sphere_z), // Position so it intersects the plane
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        ObstacleBlocker {
-            half_size: Vec3::new(sphere_scale, sphere_scale, sphere_scale), // Sphere collision box (radius = scale)
-        },
-    ));
-    
-    // Simple ambient lighting for performance
-    commands.spawn(AmbientLight {
-        color: Color::srgb(0.3, 0.3, 0.4), // Brighter ambient
-        brightness: 0.5, // Higher brightness
-        affects_lightmapped_meshes: true,
-    });
-    
-    // Main camera positioned like the official example
-    commands.spawn((
-        Camera3d::default(),
-        DistanceFog {
-            color: Color::srgb(0.05, 0.05, 0.05), // Much lighter fog
-            falloff: FogFalloff::Linear {
-                start: 30.0, // Start much further away
-                end: 100.0, // End much further away
             },
-            ..default()
-        },
-        Transform::from_xyz(15.0, 5.0, 20.0).looking_at(Vec3::ZERO, Vec3::Y),
-        GlobalTransform::default(),
-        Visibility::default(),
-        InheritedVisibility::default(),
-        CameraController, // Add controller component
-        CameraAngle { pitch: 0.0 }, // Add camera angle component
-    ));
-    
-    // Add sphere spawner
-    commands.spawn((
-        SphereSpawner {
-            timer: Timer::from_seconds(10.0, TimerMode::Repeating),
-        },
-    ));
-    
+        })
+        .run();
 }
 
 #[derive(Component)]
@@ -457,6 +178,11 @@ struct Health {
 
 
 
+/// Native HUD text showing the player's health
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Component)]
+struct HealthLabel;
+
 #[derive(Component)]
 struct CameraController;
 
@@ -480,21 +206,19 @@ struct Footstep {
     max_lifetime: f32,
 }
 
-#[derive(Component)]
-struct ObstacleBlocker {
-    half_size: Vec3,
-}
-
 #[derive(Component)]
 struct ChasingSphere {
-    speed: f32,
     last_line_of_sight: bool,
 }
 
-#[derive(Component)]
-struct SphereSpawner {
-    timer: Timer,
-}
+/// Speed a UGV gains per second
+const UGV_ACCELERATION: f32 = 4.0;
+/// Burst left behind when the player dies
+const PLAYER_DEATH_EFFECT: DeathEffect = DeathEffect::Smoke { count: 24, size: 0.2, color: [0.7, 0.0, 0.0], lifetime: 3.0 };
+/// Seconds before retrying a spawn that found no valid point
+const SPAWN_RETRY_DELAY: f32 = 1.0;
+/// Collision radius of a UGV against obstacles
+const UGV_RADIUS: f32 = 1.0;
 
 #[derive(Component)]
 struct SmokeParticle {
@@ -507,7 +231,9 @@ fn move_cube(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     time: Res<Time>,
     mut cube_query: Query<&mut Transform, With<CubeController>>,
+    obstacles: Obstacles,
 ) {
+
     for mut transform in cube_query.iter_mut() {
         let base_speed = 5.0;
         let speed = if keyboard_input.pressed(KeyCode::ShiftLeft) || keyboard_input.pressed(KeyCode::ShiftRight) {
@@ -519,14 +245,16 @@ fn move_cube(
         let dt = time.delta_secs();
         
         // W and S for forward/backward movement in the cube's local direction
+        let mut movement = Vec3::ZERO;
         if keyboard_input.pressed(KeyCode::KeyW) {
-            let forward = transform.forward();
-            transform.translation += forward * speed * dt;
+            movement += transform.forward() * speed * dt;
         }
         if keyboard_input.pressed(KeyCode::KeyS) {
-            let backward = transform.back();
-            transform.translation += backward * speed * dt;
+            movement += transform.back() * speed * dt;
         }
+        // Slide along obstacles instead of driving through them
+        let colliders = obstacles.colliders_near(transform.translation, 0.5 + movement.length());
+        transform.translation = collision::slide_move(transform.translation, movement, 0.5, &colliders);
         
         // A and D for rotation (instead of horizontal movement)
         if keyboard_input.pressed(KeyCode::KeyA) {
@@ -614,6 +342,7 @@ fn spawn_footsteps(
             // Right foot
             let right_pos = Vec3::new(footstep_pos.x, 0.05, footstep_pos.z) + right_offset + cube_transform.forward() * forward_offset;
             commands.spawn((
+                LevelEntity,
                 Mesh3d(meshes.add(Circle::new(0.1))),
                 MeshMaterial3d(materials.add(StandardMaterial {
                     base_color: Color::srgb(0.0, 0.0, 0.0), // Black
@@ -635,6 +364,7 @@ fn spawn_footsteps(
             // Left foot
             let left_pos = Vec3::new(footstep_pos.x, 0.05, footstep_pos.z) + left_offset + cube_transform.forward() * (-forward_offset);
             commands.spawn((
+                LevelEntity,
                 Mesh3d(meshes.add(Circle::new(0.1))),
                 MeshMaterial3d(materials.add(StandardMaterial {
                     base_color: Color::srgb(0.0, 0.0, 0.0), // Black
@@ -707,224 +437,233 @@ fn rotate_radar(
     }
 }
 
+/// Lets the wave director decide when to spawn, at an intensity that grows with
+/// survival time and eases off while the player is low on health. Each UGV
+/// spawns at a point the [`SpawnValidator`] accepts.
+#[allow(clippy::too_many_arguments)]
 fn spawn_spheres(
     time: Res<Time>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut spawner_query: Query<&mut SphereSpawner>,
+    mut director_query: Query<&mut WaveDirector>,
     sphere_query: Query<Entity, With<ChasingSphere>>,
+    health_query: Query<&Health, With<CubeController>>,
+    stats: Res<RunStats>,
+    nav: Res<NavGrid>,
+    mut rng: ResMut<WorldRng>,
+    validator: SpawnValidator,
 ) {
-    for mut spawner in spawner_query.iter_mut() {
-        if spawner.timer.tick(time.delta()).just_finished() {
-            // Check if we already have 10 spheres
-            if sphere_query.iter().count() >= 10 {
-                continue; // Don't spawn more spheres
-            }
-            // Spawn a new UGV at a random location on the ground
-            let angle = pseudo_random(time.elapsed_secs() * 1000.0) * 2.0 * std::f32::consts::PI;
-            let radius = pseudo_random(time.elapsed_secs() * 1001.0) * 50.0 + 30.0; // 30-80 units away
-            let x = angle.cos() * radius;
-            let z = angle.sin() * radius;
-            let y = 0.0; // Ground level
-            
-            println!("Spawning UGV at position: ({}, {}, {})", x, y, z);
-            commands.spawn((
-                SceneRoot(asset_server.load("models/antagonists/ugv/ugv.gltf#Scene0")),
-                Transform::from_xyz(x, y, z)
-                    .with_scale(Vec3::splat(0.25)), // Make UGV 2x smaller (0.5 -> 0.25)
-                GlobalTransform::default(),
-                Visibility::default(),
-                InheritedVisibility::default(),
-                ChasingSphere {
-                    speed: 3.0, // 3 units per second
-                    last_line_of_sight: false,
-                },
-            ));
-        }
+    let health_fraction = health_query.single().map_or(1.0, |health| health.current / health.max);
+    let alive = sphere_query.iter().count();
+    for mut director in director_query.iter_mut() {
+        // Radar alarms make the director run faster for a while
+        let delta = time.delta_secs() * radar::spawn_rate(&director);
+        director.alarm_time_left -= time.delta_secs();
+        let intensity = director.curve.intensity(stats.survival_time, health_fraction);
+        let Some(wave) = director.tick(delta, intensity, alive) else {
+            continue;
+        };
+        let Some(choice) = archetype::pick(&wave.choices, &mut rng).cloned() else {
+            continue;
+        };
+        // Spawn a new UGV somewhere around the player, out of sight
+        let Some(point) = validator.find(wave.min_radius, wave.max_radius, &mut rng) else {
+            director.requeue(SPAWN_RETRY_DELAY);
+            continue;
+        };
+        let archetype = &choice.archetype;
+        let (x, y, z) = (point.x, vehicle::GROUND_HEIGHT, point.z);
+        
+        debug!("Spawning {} at ({x}, {y}, {z})", choice.name);
+        let brain = UgvBrain::new(ai::patrol_loop(Vec3::new(x, y, z), &nav, &mut rng), BehaviorSpeeds::from_chase_speed(archetype.speed));
+        let steering = Steering::random(&mut rng);
+        commands.spawn(ugv_bundle(
+            &choice.name,
+            archetype,
+            choice.model.clone(),
+            Transform::from_xyz(x, y, z),
+            brain,
+            steering,
+            ugv_vehicle(archetype),
+        ));
     }
 }
 
-fn check_line_of_sight(
-    from: Vec3,
-    to: Vec3,
-    obstacle_query: &Query<(&Transform, &ObstacleBlocker), (Without<CubeController>, Without<ChasingSphere>)>,
-) -> bool {
-    let direction = (to - from).normalize();
-    let distance = from.distance(to);
-    
-    // For now, let's simplify and just check if there are any obstacles at all
-    // If there are no obstacles, always return true
-    if obstacle_query.iter().count() == 0 {
-        return true;
-    }
-    
-    // Sample points along the ray for collision detection
-    let num_samples = (distance / 2.0) as usize + 1; // Sample every 2 units
-    
-    for i in 0..num_samples {
-        let t = (i as f32) / (num_samples as f32 - 1.0);
-        let sample_point = from + direction * (distance * t);
-        
-        // Check all obstacles
-        for (obstacle_transform, obstacle_blocker) in obstacle_query.iter() {
-            let obstacle_pos = obstacle_transform.translation;
-            let obstacle_scale = obstacle_transform.scale;
-            
-            // Use the stored collision box dimensions
-            let half_size = obstacle_blocker.half_size * obstacle_scale;
-            let min = obstacle_pos - half_size;
-            let max = obstacle_pos + half_size;
-            
-            // Check if the sample point is inside the obstacle's collision box
-            if sample_point.x >= min.x && sample_point.x <= max.x &&
-               sample_point.y >= min.y && sample_point.y <= max.y &&
-               sample_point.z >= min.z && sample_point.z <= max.z {
-                return false; // Line of sight blocked
-            }
-        }
-    }
-    
-    true // No obstacles found
+/// Vehicle of a freshly spawned UGV of `archetype`
+fn ugv_vehicle(archetype: &ArchetypeDef) -> Vehicle {
+    Vehicle::new(archetype.speed, UGV_ACCELERATION, archetype.turn_rate.to_radians())
+}
+
+/// Everything a UGV of `archetype` is made of, placed by `transform` before scaling
+fn ugv_bundle(
+    name: &str,
+    archetype: &ArchetypeDef,
+    model: Handle<Scene>,
+    transform: Transform,
+    brain: UgvBrain,
+    steering: Steering,
+    vehicle: Vehicle,
+) -> impl Bundle {
+    (
+        LevelEntity,
+        SceneRoot(model),
+        transform.with_scale(Vec3::splat(archetype.scale)),
+        GlobalTransform::default(),
+        Visibility::default(),
+        InheritedVisibility::default(),
+        ChasingSphere {
+            last_line_of_sight: false,
+        },
+        Enemy::new(name, archetype),
+        brain,
+        NavPath::default(),
+        steering,
+        vehicle,
+    )
 }
 
+/// UGVs with everything `chase_cube` needs to drive them
+type UgvDriveQuery<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static mut Transform, &'static mut UgvBrain, &'static mut NavPath, &'static Steering, &'static mut Vehicle),
+    With<ChasingSphere>,
+>;
+
+/// Drives each UGV according to its brain's [`Intent`]. Moving UGVs follow A*
+/// paths around obstacles, steer clear of each other and of obstacles just
+/// ahead, and approach a chased player from their own flank. The resulting
+/// velocity is only a wish: the [`Vehicle`] turns and accelerates towards it
+/// within its limits, staying on the ground.
 fn chase_cube(
     time: Res<Time>,
-    mut sphere_query: Query<(&mut Transform, &mut ChasingSphere)>,
-    cube_query: Query<&Transform, (With<CubeController>, Without<ChasingSphere>)>,
-    obstacle_query: Query<(&Transform, &ObstacleBlocker), (Without<CubeController>, Without<ChasingSphere>)>,
+    mut sphere_query: UgvDriveQuery,
+    obstacles: Obstacles,
+    nav: Res<NavGrid>,
 ) {
-    if let Ok(cube_transform) = cube_query.single() {
-        let sphere_count = sphere_query.iter().count();
-        if sphere_count > 0 {
-            println!("Chase cube: {} UGV's found, cube at {:?}", sphere_count, cube_transform.translation);
+    let dt = time.delta_secs();
+    let positions: Vec<(Entity, Vec3)> = sphere_query
+        .iter()
+        .map(|(entity, transform, ..)| (entity, transform.translation))
+        .collect();
+
+    for (entity, mut sphere_transform, mut brain, mut path, steer, mut vehicle) in sphere_query.iter_mut() {
+        let sphere_pos = sphere_transform.translation;
+        let previous = brain.behavior();
+        let intent = brain.tick(sphere_pos, dt);
+        if brain.behavior() != previous {
+            debug!("UGV {} {:?} -> {:?}", entity, previous, brain.behavior());
         }
-        
-        for (mut sphere_transform, mut sphere) in sphere_query.iter_mut() {
-            let cube_pos = cube_transform.translation;
-            let sphere_pos = sphere_transform.translation;
-            let direction = (cube_pos - sphere_pos).normalize();
-            let distance = cube_pos.distance(sphere_pos);
-            
-            // Check if within range
-            if distance < 100.0 {
-                // Temporarily disable line of sight check to test basic chasing
-                let has_line_of_sight = true; // Always true for now
-                println!("UGV at {:?}, distance: {:.2}, line of sight: {}", sphere_pos, distance, has_line_of_sight);
-                
-                if has_line_of_sight {
-                    // Chase the cube
-                    let move_distance = sphere.speed * time.delta_secs();
-                    if distance > move_distance {
-                        let new_pos = sphere_transform.translation + direction * move_distance;
-                        sphere_transform.translation = new_pos;
-                    } else {
-                        // Close enough, move directly to cube
-                        sphere_transform.translation = cube_pos;
-                    }
-                    
-                    // Rotate UGV to face the cube
-                    let look_direction = direction;
-                    let rotation = Quat::from_rotation_y(look_direction.x.atan2(look_direction.z));
-                    sphere_transform.rotation = rotation;
-                    
-                    sphere.last_line_of_sight = true;
+
+        let (desired_velocity, face) = match intent {
+            Intent::MoveTo { target, speed } => {
+                let waypoint = path.next_waypoint(&nav, sphere_pos, target);
+                let final_leg = waypoint == target;
+                let mut velocity = if final_leg && brain.behavior() == Behavior::Chase {
+                    let aim = steering::flank_point(sphere_pos, target, steer.flank_angle, steer.flank_radius);
+                    steering::seek(sphere_pos, aim, speed)
+                } else if final_leg {
+                    steering::arrive(sphere_pos, target, speed, steer.arrive_radius)
                 } else {
-                    sphere.last_line_of_sight = false;
-                }
-            } else {
-                sphere.last_line_of_sight = false;
+                    steering::seek(sphere_pos, waypoint, speed)
+                };
+                let neighbors = positions.iter().filter(|(other, _)| *other != entity).map(|(_, position)| *position);
+                velocity += steering::separation(sphere_pos, neighbors, steer.separation_radius)
+                    * speed
+                    * steer.separation_weight;
+                velocity += steering::avoid_obstacles(sphere_pos, velocity, &obstacles, steer.avoidance_distance)
+                    * speed
+                    * steer.avoidance_weight;
+                (velocity.clamp_length_max(speed), None)
             }
-        }
+            Intent::Face(point) => {
+                path.clear();
+                (Vec3::ZERO, Some(point - sphere_pos))
+            }
+            Intent::LookAround => {
+                path.clear();
+                // Always wanting to face a quarter turn left keeps it turning at full rate
+                let heading = sphere_transform.rotation * Vec3::Z;
+                (Vec3::ZERO, Some(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2) * heading))
+            }
+            Intent::Idle => {
+                path.clear();
+                (Vec3::ZERO, None)
+            }
+        };
+
+        let (rotation, velocity) = vehicle.drive(sphere_transform.rotation, desired_velocity, face, dt);
+        let step = velocity * dt;
+        let colliders = obstacles.colliders_near(sphere_pos, UGV_RADIUS + step.length());
+        let position = collision::slide_move(sphere_pos, step, UGV_RADIUS, &colliders);
+        sphere_transform.translation = position.with_y(vehicle::GROUND_HEIGHT);
+        sphere_transform.rotation = rotation;
     }
 }
 
 fn draw_line_of_sight(
     mut gizmos: Gizmos,
-    sphere_query: Query<(&Transform, &ChasingSphere)>,
+    sphere_query: Query<(&Transform, &ChasingSphere, &UgvBrain, &Enemy)>,
     cube_query: Query<&Transform, (With<CubeController>, Without<ChasingSphere>)>,
-    obstacle_query: Query<(&Transform, &ObstacleBlocker), (Without<CubeController>, Without<ChasingSphere>)>,
+    obstacles: Obstacles,
 ) {
     if let Ok(cube_transform) = cube_query.single() {
-        for (sphere_transform, _sphere) in sphere_query.iter() {
+        for (sphere_transform, sphere, brain, enemy) in sphere_query.iter() {
             let cube_pos = cube_transform.translation;
             let sphere_pos = sphere_transform.translation;
             let distance = cube_pos.distance(sphere_pos);
+
+            // Mark where a UGV that lost sight is heading
+            if let (false, Some(last_seen)) = (sphere.last_line_of_sight, brain.last_known_position()) {
+                gizmos.line(sphere_pos, last_seen, Color::srgb(1.0, 0.5, 0.0));
+                gizmos.sphere(Isometry3d::from_translation(last_seen), 0.3, Color::srgb(1.0, 0.5, 0.0));
+            }
             
-            if distance < 100.0 {
-                let has_line_of_sight = check_line_of_sight(sphere_pos, cube_pos, &obstacle_query);
-                
-                if has_line_of_sight {
-                    // Draw a bright red line when line of sight is clear
-                    gizmos.line(
-                        sphere_transform.translation,
-                        cube_transform.translation,
-                        Color::srgb(1.0, 0.0, 0.0), // Bright red
-                    );
-                } else {
-                    // Draw a dim gray line when line of sight is blocked
-                    gizmos.line(
-                        sphere_transform.translation,
-                        cube_transform.translation,
-                        Color::srgb(0.3, 0.3, 0.3), // Dim gray
-                    );
+            if distance < enemy.sight_range {
+                let direction = (cube_pos - sphere_pos) / distance;
+                match obstacles.cast_ray(sphere_pos, direction, distance, None) {
+                    None => {
+                        // Draw a bright red line when line of sight is clear
+                        gizmos.line(sphere_pos, cube_pos, Color::srgb(1.0, 0.0, 0.0)); // Bright red
+                    }
+                    Some(hit) => {
+                        // Draw a dim gray line up to the blocking surface, with its normal
+                        let hit_point = sphere_pos + direction * hit.distance;
+                        gizmos.line(sphere_pos, hit_point, Color::srgb(0.3, 0.3, 0.3)); // Dim gray
+                        gizmos.line(hit_point, hit_point + hit.normal, Color::srgb(0.8, 0.8, 0.0));
+                    }
                 }
             }
         }
     }
 }
 
+/// UGVs that touch the player deal contact damage and blow up
 fn despawn_spheres(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    sphere_query: Query<(Entity, &Transform), With<ChasingSphere>>,
-    mut cube_query: Query<(&Transform, &mut Health), (With<CubeController>, Without<ChasingSphere>)>,
+    mut damage: MessageWriter<DamageEvent>,
+    sphere_query: Query<(Entity, &Transform, &Enemy), With<ChasingSphere>>,
+    cube_query: Query<(Entity, &Transform), With<CubeController>>,
 ) {
-    for (cube_transform, mut cube_health) in cube_query.iter_mut() {
+    for (cube_entity, cube_transform) in cube_query.iter() {
         let cube_pos = cube_transform.translation;
         
-        for (sphere_entity, sphere_transform) in sphere_query.iter() {
+        for (sphere_entity, sphere_transform, enemy) in sphere_query.iter() {
             let sphere_pos = sphere_transform.translation;
             let distance = cube_pos.distance(sphere_pos);
             
-            // If sphere is close enough to the cube (intersecting)
-            if distance < 1.5 { // 1.5 units threshold for intersection
+            // If the enemy is close enough to the cube (intersecting)
+            if distance < enemy.contact_radius {
                 // Damage the cube
-                cube_health.current = (cube_health.current - 10.0).max(0.0);
+                damage.write(DamageEvent {
+                    target: cube_entity,
+                    amount: enemy.damage,
+                    source: Some(sphere_entity),
+                    kind: DamageKind::Contact,
+                });
                 
-                // Create smoke particles at the sphere's position
-                for i in 0..8 {
-                    let angle = (i as f32) * 2.0 * std::f32::consts::PI / 8.0;
-                    let offset_x = angle.cos() * 0.5;
-                    let offset_z = angle.sin() * 0.5;
-                    
-                    commands.spawn((
-                        Mesh3d(meshes.add(Sphere::new(0.1))), // Small smoke particle
-                        MeshMaterial3d(materials.add(StandardMaterial {
-                            base_color: Color::srgb(0.3, 0.3, 0.3), // Dark gray smoke
-                            emissive: Color::srgb(0.1, 0.1, 0.1).into(), // Slightly emissive
-                            alpha_mode: AlphaMode::Blend,
-                            ..default()
-                        })),
-                        Transform::from_xyz(
-                            sphere_pos.x + offset_x,
-                            sphere_pos.y,
-                            sphere_pos.z + offset_z,
-                        ),
-                        GlobalTransform::default(),
-                        Visibility::default(),
-                        InheritedVisibility::default(),
-                        SmokeParticle {
-                            lifetime: 2.0, // 2 seconds lifetime
-                            max_lifetime: 2.0,
-                            velocity: Vec3::new(
-                                offset_x * 0.5, // Horizontal drift
-                                1.0, // Rising velocity
-                                offset_z * 0.5, // Horizontal drift
-                            ),
-                        },
-                    ));
-                }
+                spawn_death_effect(&mut commands, &mut meshes, &mut materials, enemy.death_effect, sphere_pos);
                 
                 // Despawn the sphere
                 commands.entity(sphere_entity).despawn();
@@ -933,6 +672,71 @@ fn despawn_spheres(
     }
 }
 
+/// Spawns what an enemy leaves behind at `position` when it is destroyed
+fn spawn_death_effect(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    effect: DeathEffect,
+    position: Vec3,
+) {
+    let DeathEffect::Smoke { count, size, color, lifetime } = effect else {
+        return;
+    };
+    let [r, g, b] = color;
+    // Create a ring of smoke particles drifting outwards and up
+    for i in 0..count {
+        let angle = (i as f32) * 2.0 * std::f32::consts::PI / count as f32;
+        let offset_x = angle.cos() * 0.5;
+        let offset_z = angle.sin() * 0.5;
+        
+        commands.spawn((
+            LevelEntity,
+            Mesh3d(meshes.add(Sphere::new(size))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgb(r, g, b),
+                emissive: Color::srgb(r / 3.0, g / 3.0, b / 3.0).into(), // Slightly emissive
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            })),
+            Transform::from_xyz(
+                position.x + offset_x,
+                position.y,
+                position.z + offset_z,
+            ),
+            GlobalTransform::default(),
+            Visibility::default(),
+            InheritedVisibility::default(),
+            SmokeParticle {
+                lifetime,
+                max_lifetime: lifetime,
+                velocity: Vec3::new(
+                    offset_x * 0.5, // Horizontal drift
+                    1.0, // Rising velocity
+                    offset_z * 0.5, // Horizontal drift
+                ),
+            },
+        ));
+    }
+}
+
+/// Hides a dead player in a burst of smoke
+fn kill_player(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut died: MessageReader<damage::Died>,
+    mut cube_query: Query<(&Transform, &mut Visibility), With<CubeController>>,
+) {
+    for death in died.read() {
+        let Ok((transform, mut visibility)) = cube_query.get_mut(death.entity) else {
+            continue;
+        };
+        *visibility = Visibility::Hidden;
+        spawn_death_effect(&mut commands, &mut meshes, &mut materials, PLAYER_DEATH_EFFECT, transform.translation);
+    }
+}
+
 fn update_smoke(
     time: Res<Time>,
     mut commands: Commands,
@@ -957,27 +761,61 @@ fn update_smoke(
     }
 }
 
+/// Calls a global function defined by `index.html`, with one number argument if given
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn call_page(name: &str, arg: Option<f64>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let Ok(function) = js_sys::Reflect::get(&window, &name.into()) else {
+        return;
+    };
+    if let Ok(function) = function.dyn_into::<js_sys::Function>() {
+        let _ = match arg {
+            Some(arg) => function.call1(&window, &wasm_bindgen::JsValue::from_f64(arg)),
+            None => function.call0(&window),
+        };
+    }
+}
+
 #[cfg(target_arch = "wasm32")]
 fn update_health(
     health_query: Query<&Health, (With<CubeController>, Changed<Health>)>,
 ) {
     for health in health_query.iter() {
         // Call JavaScript function to update the DOM health bar
-        if let Some(window) = web_sys::window() {
-            if let Ok(update_fn) = js_sys::Reflect::get(&window, &"updateHealth".into()) {
-                if let Ok(update_fn) = update_fn.dyn_into::<js_sys::Function>() {
-                    let _ = update_fn.call1(&window, &wasm_bindgen::JsValue::from_f64(health.current as f64));
-                }
-            }
-        }
+        call_page("updateHealth", Some(health.current as f64));
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 fn update_health(
-    _health_query: Query<&Health, (With<CubeController>, Changed<Health>)>,
+    health_query: Query<&Health, (With<CubeController>, Changed<Health>)>,
+    mut label_query: Query<&mut Text, With<HealthLabel>>,
 ) {
-    // No-op for non-WASM targets
+    for health in health_query.iter() {
+        // Same format as the DOM health text
+        for mut text in label_query.iter_mut() {
+            text.0 = format!("HP: {}", health.current.round());
+        }
+    }
+}
+
+/// Native stand-in for the web page's health text
+#[cfg(not(target_arch = "wasm32"))]
+fn health_label() -> impl Bundle {
+    (
+        HealthLabel,
+        Text::new(""),
+        TextFont { font_size: 18.0, ..default() },
+        TextColor(state::HUD_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            left: Val::Px(20.0),
+            ..default()
+        },
+    )
 }
 
 
@@ -1,50 +1,105 @@
 use bevy::prelude::*;
+use bevy::window::{MonitorSelection, WindowMode, WindowResolution};
+use bevy_3d_fog_scene::{Level0Plugin, Level0Settings};
+
+const USAGE: &str = "Usage: bevy_3d_fog_scene [OPTIONS]
+
+Options:
+  --seed <N>          World seed (default: 0)
+  --width <PX>        Window width in pixels (default: 1280)
+  --height <PX>       Window height in pixels (default: 720)
+  --fullscreen        Start in borderless fullscreen
+  --assets <PATH>     Asset root directory (default: assets)
+  -h, --help          Print this help";
+
+/// Command-line options for the native build
+struct Options {
+    seed: u64,
+    width: u32,
+    height: u32,
+    fullscreen: bool,
+    asset_root: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            width: 1280,
+            height: 720,
+            fullscreen: false,
+            asset_root: "assets".to_string(),
+        }
+    }
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => options.seed = parse_value(&arg, args.next())?,
+                "--width" => options.width = parse_value(&arg, args.next())?,
+                "--height" => options.height = parse_value(&arg, args.next())?,
+                "--fullscreen" => options.fullscreen = true,
+                "--assets" => options.asset_root = parse_value(&arg, args.next())?,
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                other => return Err(format!("unknown option `{other}`")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("`{flag}` expects a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value `{value}` for `{flag}`"))
+}
 
 fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let mode = if options.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Primary)
+    } else {
+        WindowMode::Windowed
+    };
+
     App::new()
-        .add_plugins(DefaultPlugins)
-        .add_systems(Startup, setup)
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: "Level 0".to_string(),
+                        resolution: WindowResolution::new(options.width, options.height),
+                        mode,
+                        ..default()
+                    }),
+                    ..default()
+                })
+                .set(AssetPlugin {
+                    file_path: options.asset_root,
+                    ..default()
+                }),
+        )
+        .add_plugins(Level0Plugin {
+            settings: Level0Settings {
+                seed: options.seed,
+                ..default()
+            },
+        })
         .run();
 }
-
-/// set up a simple 3D scene
-fn setup(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    // circular base
-    commands.spawn((
-        Mesh3d(meshes.add(Circle::new(40.0))),
-        MeshMaterial3d(materials.add(Color::WHITE)),
-        Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
-    ));
-    // cube (emissive glossy glass-like darker red)
-    commands.spawn((
-        Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Srgba::new(0.5, 0.05, 0.05, 0.55).into(),
-            emissive: Color::srgb(0.6, 0.06, 0.06).into(),
-            metallic: 0.9,
-            perceptual_roughness: 0.05,
-            reflectance: 0.9,
-            alpha_mode: AlphaMode::Blend,
-            cull_mode: None,
-            ..default()
-        })),
-        Transform::from_xyz(0.0, 0.5, 0.0),
-    ));
-    // light
-    commands.spawn((
-        PointLight {
-            shadows_enabled: true,
-            ..default()
-        },
-        Transform::from_xyz(4.0, 8.0, 4.0),
-    ));
-    // camera
-    commands.spawn((
-        Camera3d::default(),
-        Transform::from_xyz(-2.5, 4.5, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
-    ));
-}
\ No newline at end of file
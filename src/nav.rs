@@ -0,0 +1,469 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+
+use crate::collision::{ObstacleBlocker, PlacedCollider};
+use crate::spatial::{MovedObstacles, ObstacleIndex};
+use crate::UGV_RADIUS;
+
+/// Edge length of a navigation cell in world units
+pub const NAV_CELL_SIZE: f32 = 2.0;
+/// How far the target may drift from the planned goal before a path is re-planned
+const REPLAN_DISTANCE: f32 = 3.0;
+/// Distance at which a waypoint counts as reached
+const WAYPOINT_REACHED: f32 = 1.0;
+/// Upper bound on A* expansions, so an unreachable target cannot stall a frame
+const MAX_EXPANSIONS: usize = 10_000;
+/// Grid changes remembered for paths to check against; a path that has not looked
+/// at the grid for longer than this re-plans
+const CHANGE_LOG_LEN: usize = 64;
+
+/// Inclusive `(min, max)` range of grid cells
+type CellRect = (IVec2, IVec2);
+
+/// Walkability grid over the square ground, centered on the origin.
+///
+/// A cell is blocked when a UGV standing at its center would overlap an obstacle,
+/// so paths through free cells keep a UGV's radius away from walls. Cells are
+/// refreshed from the [`ObstacleIndex`] only under blockers that were added, moved
+/// or removed.
+#[derive(Resource, Debug)]
+pub struct NavGrid {
+    half_size: f32,
+    width: i32,
+    blocked: Vec<bool>,
+    /// Cells last covered by each blocker
+    footprints: EntityHashMap<CellRect>,
+    /// Bumped by every refresh that turns cells blocked or free
+    generation: u64,
+    /// Cells each recent generation changed, oldest first
+    changes: VecDeque<(u64, CellRect)>,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        Self::new(400.0)
+    }
+}
+
+impl NavGrid {
+    /// Empty grid over a ground of `size` x `size`
+    pub fn new(size: f32) -> Self {
+        let width = (size / NAV_CELL_SIZE).ceil() as i32;
+        Self {
+            half_size: size * 0.5,
+            width,
+            blocked: vec![false; (width * width) as usize],
+            footprints: default(),
+            generation: 0,
+            changes: VecDeque::with_capacity(CHANGE_LOG_LEN),
+        }
+    }
+
+    /// Changes each time the walkable cells do
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether any cell in `rect` turned blocked or free after `generation`.
+    /// Changes too old to be remembered count as touching every cell.
+    pub fn changed_since(&self, generation: u64, (min, max): CellRect) -> bool {
+        if generation >= self.generation {
+            return false;
+        }
+        if self.changes.front().is_none_or(|&(oldest, _)| oldest > generation + 1) {
+            return true;
+        }
+        self.changes
+            .iter()
+            .filter(|&&(changed, _)| changed > generation)
+            .any(|&(_, (changed_min, changed_max))| changed_min.cmple(max).all() && changed_max.cmpge(min).all())
+    }
+
+    pub fn cell_of(&self, point: Vec3) -> IVec2 {
+        ((point.xz() + self.half_size) / NAV_CELL_SIZE).floor().as_ivec2()
+    }
+
+    /// Center of `cell` on the ground
+    pub fn cell_center(&self, cell: IVec2) -> Vec3 {
+        let xz = (cell.as_vec2() + 0.5) * NAV_CELL_SIZE - self.half_size;
+        Vec3::new(xz.x, 0.0, xz.y)
+    }
+
+    /// Whether `point` lies over the ground the grid covers
+    pub fn contains(&self, point: Vec3) -> bool {
+        self.index(self.cell_of(point)).is_some()
+    }
+
+    /// Whether `cell` lies on the ground and is clear of obstacles
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|index| !self.blocked[index])
+    }
+
+    /// A* path from `from` to `to` over free cells, as waypoints ending at `to`.
+    ///
+    /// The start and goal cells are allowed even when blocked, since the player can
+    /// stand closer to a wall than a UGV's clearance. Returns `None` if `to` cannot
+    /// be reached within the search budget.
+    pub fn find_path(&self, from: Vec3, to: Vec3) -> Option<Vec<Vec3>> {
+        let (start, goal) = (self.cell_of(from), self.cell_of(to));
+        let start_index = self.index(start)?;
+        let goal_index = self.index(goal)?;
+        if start_index == goal_index {
+            return Some(vec![to]);
+        }
+
+        let mut cost = vec![f32::INFINITY; self.blocked.len()];
+        let mut came_from = vec![usize::MAX; self.blocked.len()];
+        let mut open = BinaryHeap::new();
+        cost[start_index] = 0.0;
+        open.push(OpenCell { estimate: octile(start, goal), index: start_index });
+
+        let mut expansions = 0;
+        while let Some(OpenCell { estimate, index }) = open.pop() {
+            if index == goal_index {
+                break;
+            }
+            let cell = self.cell_at(index);
+            // Skip stale heap entries left behind by a cheaper route
+            if estimate > cost[index] + octile(cell, goal) + f32::EPSILON {
+                continue;
+            }
+            expansions += 1;
+            if expansions > MAX_EXPANSIONS {
+                return None;
+            }
+
+            for offset in NEIGHBORS {
+                let next = cell + offset;
+                let Some(next_index) = self.index(next) else {
+                    continue;
+                };
+                if self.blocked[next_index] && next_index != goal_index {
+                    continue;
+                }
+                // No cutting corners past a blocked cell
+                let diagonal = offset.x != 0 && offset.y != 0;
+                if diagonal
+                    && (!self.is_walkable(cell + IVec2::new(offset.x, 0))
+                        || !self.is_walkable(cell + IVec2::new(0, offset.y)))
+                {
+                    continue;
+                }
+                let step = if diagonal { std::f32::consts::SQRT_2 } else { 1.0 };
+                let next_cost = cost[index] + step;
+                if next_cost < cost[next_index] {
+                    cost[next_index] = next_cost;
+                    came_from[next_index] = index;
+                    open.push(OpenCell { estimate: next_cost + octile(next, goal), index: next_index });
+                }
+            }
+        }
+
+        if came_from[goal_index] == usize::MAX {
+            return None;
+        }
+        let mut cells = Vec::new();
+        let mut current = came_from[goal_index];
+        while current != start_index {
+            cells.push(self.cell_center(self.cell_at(current)));
+            current = came_from[current];
+        }
+        cells.reverse();
+        cells.push(to);
+        Some(self.smooth(from, cells))
+    }
+
+    /// Drops waypoints that can be skipped with a straight drive over free cells
+    fn smooth(&self, from: Vec3, points: Vec<Vec3>) -> Vec<Vec3> {
+        let mut smoothed = Vec::new();
+        let mut anchor = from;
+        let mut i = 0;
+        while i < points.len() {
+            let mut furthest = i;
+            while furthest + 1 < points.len() && self.segment_walkable(anchor, points[furthest + 1]) {
+                furthest += 1;
+            }
+            smoothed.push(points[furthest]);
+            anchor = points[furthest];
+            i = furthest + 1;
+        }
+        smoothed
+    }
+
+    fn segment_walkable(&self, from: Vec3, to: Vec3) -> bool {
+        let samples = (from.xz().distance(to.xz()) / (NAV_CELL_SIZE * 0.5)).ceil() as usize;
+        (0..=samples).all(|i| {
+            let point = from.lerp(to, i as f32 / samples.max(1) as f32);
+            self.is_walkable(self.cell_of(point))
+        })
+    }
+
+    /// Cells a UGV would overlap when touching `collider`
+    fn footprint(&self, collider: &PlacedCollider) -> CellRect {
+        let (min, max) = collider.aabb();
+        let reach = Vec3::splat(UGV_RADIUS);
+        (self.cell_of(min - reach), self.cell_of(max + reach))
+    }
+
+    /// Re-evaluates every cell in `(min, max)` against the obstacles in `index`,
+    /// logging the cells that changed under a new generation
+    fn refresh(&mut self, (min, max): CellRect, index: &ObstacleIndex) {
+        let mut changed: Option<CellRect> = None;
+        for x in min.x.max(0)..=max.x.min(self.width - 1) {
+            for z in min.y.max(0)..=max.y.min(self.width - 1) {
+                let cell = IVec2::new(x, z);
+                let center = self.cell_center(cell) + Vec3::Y * UGV_RADIUS;
+                let blocked = index
+                    .colliders_near(center, UGV_RADIUS)
+                    .iter()
+                    .any(|collider| collider.depenetrate(center, UGV_RADIUS).is_some());
+                let cell_index = (z * self.width + x) as usize;
+                if self.blocked[cell_index] != blocked {
+                    self.blocked[cell_index] = blocked;
+                    changed = Some(changed.map_or((cell, cell), |(min, max)| (min.min(cell), max.max(cell))));
+                }
+            }
+        }
+        if let Some(changed) = changed {
+            self.generation += 1;
+            if self.changes.len() == CHANGE_LOG_LEN {
+                self.changes.pop_front();
+            }
+            self.changes.push_back((self.generation, changed));
+        }
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let inside = cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.width;
+        inside.then(|| (cell.y * self.width + cell.x) as usize)
+    }
+
+    fn cell_at(&self, index: usize) -> IVec2 {
+        IVec2::new(index as i32 % self.width, index as i32 / self.width)
+    }
+}
+
+const NEIGHBORS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Grid distance with diagonal moves, in cells
+fn octile(from: IVec2, to: IVec2) -> f32 {
+    let delta = (to - from).abs();
+    let (long, short) = (delta.max_element() as f32, delta.min_element() as f32);
+    long + (std::f32::consts::SQRT_2 - 1.0) * short
+}
+
+/// Open-set entry ordered so the heap pops the lowest estimate first
+#[derive(PartialEq)]
+struct OpenCell {
+    estimate: f32,
+    index: usize,
+}
+
+impl Eq for OpenCell {}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The route a UGV is following, kept until its target moves far enough away or
+/// the grid changes along it
+#[derive(Component, Debug, Default)]
+pub struct NavPath {
+    waypoints: Vec<Vec3>,
+    /// Target the current waypoints were planned for
+    goal: Option<Vec3>,
+    /// Cells around the route, from where it was planned to its goal
+    bounds: CellRect,
+    /// Last [`NavGrid::generation`] the route was checked against
+    generation: u64,
+}
+
+impl NavPath {
+    /// Point to drive at from `position` on the way to `target`.
+    ///
+    /// Re-plans when there is no path yet, `target` has drifted more than
+    /// `REPLAN_DISTANCE` from the planned goal, or cells within the route's bounds
+    /// turned blocked or free since it was last checked. Changes elsewhere on the
+    /// grid leave the route alone. Falls back to driving straight at `target` when
+    /// no path exists.
+    pub fn next_waypoint(&mut self, nav: &NavGrid, position: Vec3, target: Vec3) -> Vec3 {
+        let drifted = self.goal.is_none_or(|goal| goal.xz().distance(target.xz()) > REPLAN_DISTANCE);
+        if drifted || nav.changed_since(self.generation, self.bounds) {
+            self.waypoints = nav.find_path(position, target).unwrap_or_default();
+            self.goal = Some(target);
+            self.bounds = route_bounds(nav, position, target, &self.waypoints);
+        }
+        self.generation = nav.generation();
+        while !self.waypoints.is_empty() && self.waypoints[0].xz().distance(position.xz()) < WAYPOINT_REACHED {
+            self.waypoints.remove(0);
+        }
+        // The last leg heads for the live target rather than where it was when planned
+        match self.waypoints.as_slice() {
+            [] | [_] => target,
+            [next, ..] => *next,
+        }
+    }
+
+    /// Forgets the route, e.g. when the UGV stops pursuing
+    pub fn clear(&mut self) {
+        self.waypoints.clear();
+        self.goal = None;
+    }
+
+    pub fn waypoints(&self) -> &[Vec3] {
+        &self.waypoints
+    }
+}
+
+/// Cells covered by a route from `from` through `waypoints` to `target`, plus a
+/// margin of one cell for the corners the path was kept clear of
+fn route_bounds(nav: &NavGrid, from: Vec3, target: Vec3, waypoints: &[Vec3]) -> CellRect {
+    let start = nav.cell_of(from);
+    let (min, max) = waypoints
+        .iter()
+        .chain([&target])
+        .map(|point| nav.cell_of(*point))
+        .fold((start, start), |(min, max), cell| (min.min(cell), max.max(cell)));
+    (min - IVec2::ONE, max + IVec2::ONE)
+}
+
+/// Refreshes [`NavGrid`] cells under blockers that were added, moved or removed.
+///
+/// Runs after the obstacle index has taken in the same changes.
+pub(crate) fn sync_nav_grid(
+    mut nav: ResMut<NavGrid>,
+    index: Res<ObstacleIndex>,
+    changed: Query<(Entity, &Transform, &ObstacleBlocker), MovedObstacles>,
+    mut removed: RemovedComponents<ObstacleBlocker>,
+) {
+    for entity in removed.read() {
+        if let Some(footprint) = nav.footprints.remove(&entity) {
+            nav.refresh(footprint, &index);
+        }
+    }
+    for (entity, transform, blocker) in changed.iter() {
+        let footprint = nav.footprint(&PlacedCollider::new(entity, transform, blocker));
+        if let Some(old) = nav.footprints.insert(entity, footprint) {
+            if old != footprint {
+                nav.refresh(old, &index);
+            }
+        }
+        nav.refresh(footprint, &index);
+    }
+}
+
+/// Draws the remaining route of every UGV
+pub(crate) fn draw_paths(mut gizmos: Gizmos, paths: Query<(&Transform, &NavPath)>) {
+    for (transform, path) in paths.iter() {
+        if path.waypoints.is_empty() {
+            continue;
+        }
+        let points = std::iter::once(transform.translation)
+            .chain(path.waypoints.iter().copied())
+            .map(|point| Vec3::new(point.x, 0.2, point.z));
+        gizmos.linestrip(points, Color::srgb(0.0, 0.8, 0.8));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_replan_when_the_grid_changes() {
+        let mut nav = NavGrid::new(40.0);
+        let mut index = ObstacleIndex::default();
+        let (from, to) = (Vec3::new(-10.0, 0.0, 0.0), Vec3::new(10.0, 0.0, 0.0));
+        let mut path = NavPath::default();
+        assert_eq!(path.next_waypoint(&nav, from, to), to);
+
+        // A wall drops across the straight route
+        let wall = PlacedCollider::new(
+            Entity::from_raw_u32(1).unwrap(),
+            &Transform::from_xyz(0.0, 1.0, 0.0),
+            &ObstacleBlocker::cuboid(Vec3::new(0.5, 1.0, 5.0)),
+        );
+        index.insert(wall);
+        let generation = nav.generation();
+        nav.refresh(nav.footprint(&wall), &index);
+        assert_ne!(nav.generation(), generation);
+
+        let waypoint = path.next_waypoint(&nav, from, to);
+        assert_ne!(waypoint, to, "still driving straight through the wall");
+        assert!(path.waypoints().iter().all(|point| nav.is_walkable(nav.cell_of(*point)) || *point == to));
+
+        // Refreshing cells that stay the same keeps existing paths
+        let generation = nav.generation();
+        nav.refresh(nav.footprint(&wall), &index);
+        assert_eq!(nav.generation(), generation);
+    }
+
+    #[test]
+    fn changes_away_from_the_route_keep_the_path() {
+        let mut nav = NavGrid::new(40.0);
+        let mut index = ObstacleIndex::default();
+        let (from, to) = (Vec3::new(-10.0, 0.0, 0.0), Vec3::new(10.0, 0.0, 0.0));
+        let mut path = NavPath::default();
+        path.next_waypoint(&nav, from, to);
+        // Mark the planned route so a re-plan would show
+        let marker = Vec3::new(-5.0, 0.0, 0.0);
+        path.waypoints.insert(0, marker);
+
+        // A prop appears well off to the side, like a radar turning in a corner
+        let prop = PlacedCollider::new(
+            Entity::from_raw_u32(1).unwrap(),
+            &Transform::from_xyz(0.5, 1.0, 15.5),
+            &ObstacleBlocker::cuboid(Vec3::splat(1.0)),
+        );
+        index.insert(prop);
+        let generation = nav.generation();
+        nav.refresh(nav.footprint(&prop), &index);
+        assert_ne!(nav.generation(), generation);
+        assert!(!nav.changed_since(generation, path.bounds));
+
+        assert_eq!(path.next_waypoint(&nav, from, to), marker);
+        assert_eq!(path.generation, nav.generation());
+    }
+
+    #[test]
+    fn forgotten_changes_count_as_touching_the_route() {
+        let mut nav = NavGrid::new(40.0);
+        let mut index = ObstacleIndex::default();
+        let route = (nav.cell_of(Vec3::new(-10.0, 0.0, 0.0)), nav.cell_of(Vec3::new(10.0, 0.0, 0.0)));
+        let prop = PlacedCollider::new(
+            Entity::from_raw_u32(1).unwrap(),
+            &Transform::from_xyz(0.5, 1.0, 15.5),
+            &ObstacleBlocker::cuboid(Vec3::splat(1.0)),
+        );
+        index.insert(prop);
+        // Flip the same cells more often than the log remembers
+        for _ in 0..=CHANGE_LOG_LEN / 2 {
+            nav.refresh(nav.footprint(&prop), &index);
+            index.remove(prop.entity);
+            nav.refresh(nav.footprint(&prop), &index);
+            index.insert(prop);
+        }
+        assert!(nav.changed_since(0, route));
+        assert!(!nav.changed_since(nav.generation() - 1, route));
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::ai::{Perceived, Stimulus, UgvBrain};
+use crate::archetype::Enemy;
+use crate::collision::Obstacles;
+use crate::radar::{Radar, RadarAlert};
+use crate::CubeController;
+
+/// Seconds between footstep noises while the player is moving
+const STEP_INTERVAL: f32 = 0.3;
+/// Speed above which the player counts as sprinting, in units per second
+const SPRINT_SPEED: f32 = 7.5;
+/// How far a walking footstep carries in the open
+const WALK_RADIUS: f32 = 12.0;
+/// How far a sprinting footstep carries in the open
+const SPRINT_RADIUS: f32 = 30.0;
+/// Fraction of a noise's radius that still carries through an obstacle
+const OCCLUDED_FACTOR: f32 = 0.4;
+/// Fastest the player moves on foot, while sprinting, in units per second
+const PLAYER_MAX_SPEED: f32 = 10.0;
+/// Per-frame moves longer than this many times the fastest step are teleports such
+/// as a restart, not steps. Scaled by frame time so slow frames still make noise.
+const TELEPORT_FACTOR: f32 = 2.0;
+
+/// A sound made somewhere in the world
+#[derive(Message, Debug, Clone, Copy)]
+pub struct Noise {
+    pub position: Vec3,
+    /// Distance at which the noise can still be heard with nothing in the way
+    pub radius: f32,
+}
+
+impl Noise {
+    /// Whether `listener`, standing at `position`, hears this noise. `hearing`
+    /// scales the radius for keener or duller listeners; obstacles between them
+    /// shrink it to `OCCLUDED_FACTOR` of its open-air value. The listener's own
+    /// collider never muffles what it hears.
+    pub fn audible_from(&self, listener: Entity, position: Vec3, hearing: f32, obstacles: &Obstacles) -> bool {
+        let radius = self.radius * hearing;
+        let distance = position.distance(self.position);
+        distance <= radius
+            && (distance <= radius * OCCLUDED_FACTOR
+                || obstacles.line_of_sight(self.position, position, Some(listener)))
+    }
+}
+
+/// Emits a footstep noise every `STEP_INTERVAL` while the player moves, louder
+/// when sprinting. Standing still is silent.
+pub(crate) fn emit_player_noise(
+    time: Res<Time>,
+    mut noises: MessageWriter<Noise>,
+    cube_query: Query<&Transform, With<CubeController>>,
+    mut last_position: Local<Option<Vec3>>,
+    mut step_timer: Local<f32>,
+) {
+    let Ok(cube_transform) = cube_query.single() else {
+        return;
+    };
+    let position = cube_transform.translation;
+    let moved = last_position.replace(position).map_or(0.0, |last| last.xz().distance(position.xz()));
+    let dt = time.delta_secs();
+    if moved <= f32::EPSILON || dt <= 0.0 || moved > TELEPORT_FACTOR * PLAYER_MAX_SPEED * dt {
+        *step_timer = 0.0;
+        return;
+    }
+
+    *step_timer -= dt;
+    if *step_timer > 0.0 {
+        return;
+    }
+    *step_timer = STEP_INTERVAL;
+    let radius = if moved / dt > SPRINT_SPEED { SPRINT_RADIUS } else { WALK_RADIUS };
+    noises.write(Noise { position, radius });
+}
+
+/// Passes noises on to the UGVs and radars that hear them
+pub(crate) fn hear_noises(
+    mut noises: MessageReader<Noise>,
+    mut perceived: MessageWriter<Perceived>,
+    mut alerts: MessageWriter<RadarAlert>,
+    ugv_query: Query<(Entity, &Transform, &Enemy), With<UgvBrain>>,
+    mut radar_query: Query<(Entity, &Transform, &mut Radar)>,
+    obstacles: Obstacles,
+) {
+    for noise in noises.read() {
+        for (ugv, transform, enemy) in ugv_query.iter() {
+            if noise.audible_from(ugv, transform.translation, enemy.hearing, &obstacles) {
+                perceived.write(Perceived { ugv, stimulus: Stimulus::Noise(noise.position) });
+            }
+        }
+        for (radar, transform, mut radar_state) in radar_query.iter_mut() {
+            if noise.audible_from(radar, transform.translation, 1.0, &obstacles) && radar_state.ready_to_alert() {
+                alerts.write(RadarAlert { radar, position: noise.position });
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+use crate::state::{overlay_root, overlay_text, GameState};
+use crate::Level0Settings;
+
+/// Which page of the pause overlay is showing
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PauseMenu {
+    #[default]
+    Main,
+    Settings,
+}
+
+/// Marks the pause overlay so it can be rebuilt when its page changes
+#[derive(Component)]
+pub(crate) struct PauseOverlay;
+
+/// Pauses on Escape or P
+pub(crate) fn pause_on_key(keys: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<GameState>>) {
+    if keys.any_just_pressed([KeyCode::Escape, KeyCode::KeyP]) {
+        next_state.set(GameState::Paused);
+    }
+}
+
+/// Pauses when the game window loses focus, e.g. on alt-tab
+pub(crate) fn pause_on_focus_loss(
+    mut focus: MessageReader<WindowFocused>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if focus.read().any(|event| !event.focused) {
+        next_state.set(GameState::Paused);
+    }
+}
+
+/// Set by the page's `visibilitychange` listener when the tab is hidden.
+/// The browser stops rendering hidden tabs, so this is only seen once the tab
+/// is back, before the first frame runs any gameplay.
+#[cfg(target_arch = "wasm32")]
+static PAGE_HIDDEN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Listens for the page being hidden, such as when switching browser tabs
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn watch_page_visibility() {
+    use wasm_bindgen::prelude::*;
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+    let on_change = Closure::<dyn FnMut()>::new(|| {
+        let hidden = web_sys::window()
+            .and_then(|window| window.document())
+            .is_some_and(|document| document.visibility_state() == web_sys::VisibilityState::Hidden);
+        if hidden {
+            PAGE_HIDDEN.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    });
+    let _ = document.add_event_listener_with_callback("visibilitychange", on_change.as_ref().unchecked_ref());
+    // The listener lives as long as the page
+    on_change.forget();
+}
+
+/// Pauses a running game once the page has been hidden
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn pause_when_hidden(state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>) {
+    if PAGE_HIDDEN.swap(false, std::sync::atomic::Ordering::Relaxed) && *state.get() == GameState::Playing {
+        next_state.set(GameState::Paused);
+    }
+}
+
+/// Freezes virtual time, so timers, footprints and smoke stop with the game
+pub(crate) fn enter_pause(mut time: ResMut<Time<Virtual>>, mut menu: ResMut<PauseMenu>) {
+    time.pause();
+    *menu = PauseMenu::Main;
+}
+
+pub(crate) fn exit_pause(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Keys of the pause overlay: resume and settings on the main page, toggles on
+/// the settings page. Restart is handled by `state::restart`.
+pub(crate) fn pause_menu_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut menu: ResMut<PauseMenu>,
+    mut settings: ResMut<Level0Settings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    match *menu {
+        PauseMenu::Main => {
+            if keys.any_just_pressed([KeyCode::Escape, KeyCode::KeyP]) {
+                next_state.set(GameState::Playing);
+            } else if keys.just_pressed(KeyCode::KeyS) {
+                *menu = PauseMenu::Settings;
+            }
+        }
+        PauseMenu::Settings => {
+            if keys.any_just_pressed([KeyCode::Escape, KeyCode::Backspace]) {
+                *menu = PauseMenu::Main;
+            } else if keys.just_pressed(KeyCode::KeyG) {
+                settings.debug_gizmos = !settings.debug_gizmos;
+            }
+        }
+    }
+}
+
+/// Rebuilds the pause overlay whenever its page or the settings it shows change
+pub(crate) fn update_pause_overlay(
+    mut commands: Commands,
+    menu: Res<PauseMenu>,
+    settings: Res<Level0Settings>,
+    overlay_query: Query<Entity, With<PauseOverlay>>,
+) {
+    if !menu.is_changed() && !settings.is_changed() {
+        return;
+    }
+    for entity in overlay_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let on_off = |enabled: bool| if enabled { "on" } else { "off" };
+    let overlay = (DespawnOnExit(GameState::Paused), PauseOverlay, overlay_root());
+    match *menu {
+        PauseMenu::Main => commands.spawn((
+            overlay,
+            children![
+                overlay_text("PAUSED", 48.0),
+                overlay_text("Esc / P  Resume", 18.0),
+                overlay_text("S  Settings", 18.0),
+                overlay_text("R  Restart", 18.0),
+                overlay_text("F5  Save    F9  Load", 18.0),
+            ],
+        )),
+        PauseMenu::Settings => commands.spawn((
+            overlay,
+            children![
+                overlay_text("SETTINGS", 48.0),
+                overlay_text(format!("G  Debug gizmos: {}", on_off(settings.debug_gizmos)), 18.0),
+                overlay_text(format!("F3  Collider wireframes: {}", on_off(settings.debug_colliders)), 18.0),
+                overlay_text("Esc  Back", 18.0),
+            ],
+        )),
+    };
+}
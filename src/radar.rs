@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use crate::ai::{Perceived, Stimulus};
+use crate::collision::Obstacles;
+use crate::waves::WaveDirector;
+use crate::{ChasingSphere, CubeController};
+
+/// Seconds between alerts from one radar while the player stays in its cone
+const ALERT_COOLDOWN: f32 = 1.0;
+/// How long the wave director stays sped up after an alert, in seconds
+const ALARM_DURATION: f32 = 10.0;
+/// Wave director speed-up while an alarm is active
+const ALARM_SPAWN_RATE: f32 = 2.0;
+
+/// Detection cone of a radar, pointing along the entity's forward (-Z) axis on the
+/// ground plane, so it sweeps as the radar rotates
+#[derive(Component, Debug, Clone)]
+pub struct Radar {
+    /// Detection distance in world units
+    pub range: f32,
+    /// Full opening angle of the cone, in radians
+    pub fov: f32,
+    /// UGVs within this distance of the radar answer its alerts
+    pub alert_radius: f32,
+    /// Whether the player was inside the cone and in sight last frame
+    pub detecting: bool,
+    cooldown: f32,
+}
+
+impl Radar {
+    pub fn new(range: f32, fov: f32, alert_radius: f32) -> Self {
+        Self { range, fov, alert_radius, detecting: false, cooldown: 0.0 }
+    }
+
+    /// Whether `point` lies inside the cone of a radar placed by `transform`
+    pub fn covers(&self, transform: &Transform, point: Vec3) -> bool {
+        let offset = (point - transform.translation).xz();
+        let Some(forward) = transform.forward().xz().try_normalize() else {
+            return false;
+        };
+        let distance = offset.length();
+        distance <= self.range && (distance <= f32::EPSILON || forward.angle_to(offset).abs() <= self.fov * 0.5)
+    }
+
+    /// Clears the alert cooldown and the last sighting, as at the start of a run
+    pub fn reset(&mut self) {
+        self.detecting = false;
+        self.cooldown = 0.0;
+    }
+
+    /// Whether the radar may alert now, starting its cooldown if so
+    pub(crate) fn ready_to_alert(&mut self) -> bool {
+        if self.cooldown > 0.0 {
+            return false;
+        }
+        self.cooldown = ALERT_COOLDOWN;
+        true
+    }
+}
+
+/// A radar saw or heard the player
+#[derive(Message, Debug, Clone, Copy)]
+pub struct RadarAlert {
+    pub radar: Entity,
+    /// Where the player was detected
+    pub position: Vec3,
+}
+
+/// Checks every radar cone for the player and raises an alert on a clear sighting
+pub(crate) fn sweep_radars(
+    time: Res<Time>,
+    mut alerts: MessageWriter<RadarAlert>,
+    mut radar_query: Query<(Entity, &Transform, &mut Radar)>,
+    cube_query: Query<&Transform, With<CubeController>>,
+    obstacles: Obstacles,
+) {
+    let Ok(cube_transform) = cube_query.single() else {
+        return;
+    };
+    let cube_pos = cube_transform.translation;
+    for (entity, transform, mut radar) in radar_query.iter_mut() {
+        radar.cooldown -= time.delta_secs();
+        // The radar's own collider sits around its origin and would block every sighting
+        radar.detecting = radar.covers(transform, cube_pos)
+            && obstacles.line_of_sight(transform.translation, cube_pos, Some(entity));
+        if radar.detecting && radar.ready_to_alert() {
+            alerts.write(RadarAlert { radar: entity, position: cube_pos });
+        }
+    }
+}
+
+/// Sends UGVs near an alerting radar to the player's position and hurries the waves along
+pub(crate) fn raise_alarms(
+    mut alerts: MessageReader<RadarAlert>,
+    mut perceived: MessageWriter<Perceived>,
+    radar_query: Query<(&Transform, &Radar)>,
+    sphere_query: Query<(Entity, &Transform), With<ChasingSphere>>,
+    mut director_query: Query<&mut WaveDirector>,
+) {
+    for alert in alerts.read() {
+        let Ok((radar_transform, radar)) = radar_query.get(alert.radar) else {
+            continue;
+        };
+        info!("Radar {} detected the player at {:?}", alert.radar, alert.position);
+        for (ugv, transform) in sphere_query.iter() {
+            if transform.translation.distance(radar_transform.translation) <= radar.alert_radius {
+                perceived.write(Perceived { ugv, stimulus: Stimulus::Alarm(alert.position) });
+            }
+        }
+        for mut director in director_query.iter_mut() {
+            director.alarm_time_left = ALARM_DURATION;
+        }
+    }
+}
+
+/// Wave director time multiplier while a radar alarm is active
+pub(crate) fn spawn_rate(director: &WaveDirector) -> f32 {
+    if director.alarm_time_left > 0.0 {
+        ALARM_SPAWN_RATE
+    } else {
+        1.0
+    }
+}
+
+/// Draws each radar's detection cone, red while it sees the player
+pub(crate) fn draw_radar_cones(mut gizmos: Gizmos, radar_query: Query<(&Transform, &Radar)>) {
+    for (transform, radar) in radar_query.iter() {
+        let color = if radar.detecting { Color::srgb(1.0, 0.0, 0.0) } else { Color::srgb(0.9, 0.7, 0.0) };
+        let Some(forward) = transform.forward().xz().try_normalize() else {
+            continue;
+        };
+        let origin = transform.translation.with_y(0.2);
+        let edge = |angle: f32| {
+            let direction = Vec2::from_angle(angle).rotate(forward) * radar.range;
+            origin + Vec3::new(direction.x, 0.0, direction.y)
+        };
+        let half = radar.fov * 0.5;
+        let arc = (0..=16).map(|i| edge(-half + radar.fov * i as f32 / 16.0));
+        gizmos.linestrip(std::iter::once(origin).chain(arc).chain(std::iter::once(origin)), color);
+    }
+}
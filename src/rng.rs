@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Deterministic random source for world layout and spawning.
+///
+/// Everything random in a session is drawn from this one stream, so the same
+/// seed always reproduces the same world and the same UGV spawns.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct WorldRng {
+    seed: u64,
+    state: u64,
+}
+
+impl WorldRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// The seed this generator was created with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Next raw value (SplitMix64)
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`
+    pub fn next_f32(&mut self) -> f32 {
+        // Top 24 bits fit exactly in an f32 mantissa
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform value in `[min, max)`
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + self.next_f32() * (max - min)
+    }
+
+    /// Uniform angle in `[0, 2π)`
+    pub fn angle(&mut self) -> f32 {
+        self.next_f32() * std::f32::consts::TAU
+    }
+}
+
+/// Reads `?seed=<u64>` from the page URL
+#[cfg(target_arch = "wasm32")]
+pub fn seed_from_url() -> Option<u64> {
+    let search = web_sys::window()?.location().search().ok()?;
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "seed")
+        .and_then(|(_, value)| value.parse().ok())
+}
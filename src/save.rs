@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::{BehaviorSpeeds, BrainState, UgvBrain};
+use crate::archetype::{ArchetypeRegistry, Enemy};
+use crate::level::{CurrentLevel, LevelEntity};
+use crate::state::{GameState, RunStats};
+use crate::steering::Steering;
+use crate::vehicle::Vehicle;
+use crate::waves::{DirectorState, WaveDirector};
+use crate::{ugv_bundle, CubeController, Health, Level0Settings, WorldRng};
+
+/// Version written into new saves. Bump it whenever the saved data changes
+/// shape; fields added later need a serde default so older saves still load,
+/// and anything else gets a migration in [`parse`].
+pub const SAVE_VERSION: u32 = 1;
+
+/// A running game, as written to disk or browser storage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    /// Seed the world was built from
+    pub seed: u64,
+    /// Random state at the time of saving, so the run continues as it would have
+    pub rng: WorldRng,
+    pub player: PlayerState,
+    pub ugvs: Vec<UgvState>,
+    pub waves: Option<DirectorState>,
+    pub stats: RunStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerState {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub health: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UgvState {
+    /// Name in the archetype registry
+    pub archetype: String,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub brain: BrainState,
+    pub steering: Steering,
+    pub vehicle: Vehicle,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SaveError {
+    #[error("could not access save file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not write save: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("could not read save: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("save version {0} is newer than this game supports ({SAVE_VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("browser storage failed: {0}")]
+    Storage(String),
+    #[error("no saved game")]
+    NotFound,
+}
+
+/// Reads a save of any supported version
+pub fn parse(text: &str) -> Result<SaveGame, SaveError> {
+    #[derive(Deserialize)]
+    struct Header {
+        version: u32,
+    }
+
+    let Header { version } = ron::from_str(text)?;
+    match version {
+        1 => Ok(ron::from_str(text)?),
+        _ => Err(SaveError::UnsupportedVersion(version)),
+    }
+}
+
+pub fn write(save: &SaveGame) -> Result<String, SaveError> {
+    Ok(ron::ser::to_string_pretty(save, ron::ser::PrettyConfig::default())?)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn store(slot: &str, text: &str) -> Result<(), SaveError> {
+    Ok(std::fs::write(slot, text)?)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch(slot: &str) -> Result<String, SaveError> {
+    match std::fs::read_to_string(slot) {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Err(SaveError::NotFound),
+        result => Ok(result?),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, SaveError> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| SaveError::Storage("localStorage is unavailable".to_string()))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn store(slot: &str, text: &str) -> Result<(), SaveError> {
+    local_storage()?
+        .set_item(slot, text)
+        .map_err(|error| SaveError::Storage(format!("{error:?}")))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn fetch(slot: &str) -> Result<String, SaveError> {
+    local_storage()?
+        .get_item(slot)
+        .map_err(|error| SaveError::Storage(format!("{error:?}")))?
+        .ok_or(SaveError::NotFound)
+}
+
+/// A loaded save waiting for its world to be rebuilt
+#[derive(Resource)]
+pub(crate) struct PendingLoad(SaveGame);
+
+/// Writes the running game to `Level0Settings::save_slot`
+pub(crate) fn save_game(
+    settings: Res<Level0Settings>,
+    rng: Res<WorldRng>,
+    stats: Res<RunStats>,
+    player_query: Query<(&Transform, &Health), With<CubeController>>,
+    ugv_query: Query<(&Transform, &Enemy, &UgvBrain, &Steering, &Vehicle)>,
+    director_query: Query<&WaveDirector>,
+) {
+    let Ok((player_transform, health)) = player_query.single() else {
+        return;
+    };
+    let save = SaveGame {
+        version: SAVE_VERSION,
+        seed: rng.seed(),
+        rng: rng.clone(),
+        player: PlayerState {
+            translation: player_transform.translation.to_array(),
+            rotation: player_transform.rotation.to_array(),
+            health: health.current,
+        },
+        ugvs: ugv_query
+            .iter()
+            .map(|(transform, enemy, brain, steering, vehicle)| UgvState {
+                archetype: enemy.archetype.clone(),
+                translation: transform.translation.to_array(),
+                rotation: transform.rotation.to_array(),
+                brain: brain.save(),
+                steering: *steering,
+                vehicle: *vehicle,
+            })
+            .collect(),
+        waves: director_query.iter().next().map(WaveDirector::save),
+        stats: stats.clone(),
+    };
+    match write(&save).and_then(|text| store(&settings.save_slot, &text)) {
+        Ok(()) => info!("Saved game to {}", settings.save_slot),
+        Err(error) => warn!("Could not save game: {error}"),
+    }
+}
+
+/// Reads `Level0Settings::save_slot` and rebuilds the world from its seed;
+/// [`apply_pending_load`] restores the rest once the level is back
+pub(crate) fn load_game(
+    mut commands: Commands,
+    mut settings: ResMut<Level0Settings>,
+    mut level: ResMut<CurrentLevel>,
+    mut rng: ResMut<WorldRng>,
+    level_entities: Query<Entity, With<LevelEntity>>,
+) {
+    let save = match fetch(&settings.save_slot).and_then(|text| parse(&text)) {
+        Ok(save) => save,
+        Err(error) => {
+            warn!("Could not load game: {error}");
+            return;
+        }
+    };
+    info!("Loading game from {} (seed {})", settings.save_slot, save.seed);
+    for entity in level_entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    settings.seed = save.seed;
+    *rng = WorldRng::new(save.seed);
+    level.spawned = false;
+    commands.insert_resource(PendingLoad(save));
+}
+
+/// Puts the player, UGVs, waves and score back once the saved world is spawned
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn apply_pending_load(
+    mut commands: Commands,
+    pending: Res<PendingLoad>,
+    level: Res<CurrentLevel>,
+    registries: Res<Assets<ArchetypeRegistry>>,
+    asset_server: Res<AssetServer>,
+    mut player_query: Query<(&mut Transform, &mut Health, &mut Visibility), With<CubeController>>,
+    mut director_query: Query<&mut WaveDirector>,
+    mut rng: ResMut<WorldRng>,
+    mut stats: ResMut<RunStats>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !level.spawned {
+        return;
+    }
+    // The rebuilt level's entities appear a frame after it is marked spawned
+    let Ok((mut transform, mut health, mut visibility)) = player_query.single_mut() else {
+        return;
+    };
+    let Some(registry) = registries.get(&level.archetypes) else {
+        return;
+    };
+    let save = &pending.0;
+
+    *transform = Transform::from_translation(Vec3::from_array(save.player.translation))
+        .with_rotation(Quat::from_array(save.player.rotation));
+    health.current = save.player.health.min(health.max);
+    *visibility = Visibility::Inherited;
+
+    for ugv in &save.ugvs {
+        let Some(archetype) = registry.archetypes.get(&ugv.archetype) else {
+            warn!("Saved UGV has unknown archetype {:?}; leaving it out", ugv.archetype);
+            continue;
+        };
+        commands.spawn(ugv_bundle(
+            &ugv.archetype,
+            archetype,
+            asset_server.load(&archetype.model),
+            Transform::from_translation(Vec3::from_array(ugv.translation))
+                .with_rotation(Quat::from_array(ugv.rotation)),
+            UgvBrain::restore(&ugv.brain, BehaviorSpeeds::from_chase_speed(archetype.speed)),
+            ugv.steering,
+            ugv.vehicle,
+        ));
+    }
+    if let Some(waves) = &save.waves {
+        for mut director in director_query.iter_mut() {
+            director.restore(waves);
+        }
+    }
+    *rng = save.rng.clone();
+    *stats = save.stats.clone();
+
+    commands.remove_resource::<PendingLoad>();
+    next_state.set(GameState::Playing);
+    info!("Loaded game with {} UGVs", save.ugvs.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::Behavior;
+    use crate::waves::WavePhase;
+
+    fn sample() -> SaveGame {
+        let mut rng = WorldRng::new(42);
+        let steering = Steering::random(&mut rng);
+        SaveGame {
+            version: SAVE_VERSION,
+            seed: 42,
+            rng,
+            player: PlayerState { translation: [1.0, 0.5, -2.0], rotation: [0.0, 0.6, 0.0, 0.8], health: 70.0 },
+            ugvs: vec![UgvState {
+                archetype: "scout".to_string(),
+                translation: [12.0, 0.0, 3.5],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                brain: BrainState {
+                    behavior: Behavior::Search,
+                    patrol_route: vec![[10.0, 0.0, 0.0], [0.0, 0.0, 10.0]],
+                    next_waypoint: 1,
+                    last_known_position: Some([4.0, 0.0, 4.0]),
+                    time_in_behavior: 1.25,
+                    search_time_left: 3.5,
+                },
+                steering,
+                vehicle: Vehicle::new(4.5, 8.0, 2.4),
+            }],
+            waves: Some(DirectorState {
+                number: 3,
+                phase: WavePhase::Spawning { remaining: 2 },
+                time_left: 1.5,
+                alarm_time_left: 4.0,
+            }),
+            stats: RunStats { survival_time: 95.5, hits_taken: 4 },
+        }
+    }
+
+    #[test]
+    fn save_round_trips() {
+        let save = sample();
+        let text = write(&save).unwrap();
+        let loaded = parse(&text).unwrap();
+
+        assert_eq!(loaded.version, SAVE_VERSION);
+        assert_eq!(loaded.seed, 42);
+        assert_eq!(loaded.player.translation, save.player.translation);
+        assert_eq!(loaded.player.health, 70.0);
+        assert_eq!(loaded.ugvs.len(), 1);
+        assert_eq!(loaded.ugvs[0].archetype, "scout");
+        assert_eq!(loaded.ugvs[0].brain.behavior, Behavior::Search);
+        assert_eq!(loaded.waves.map(|waves| waves.phase), Some(WavePhase::Spawning { remaining: 2 }));
+        assert_eq!(loaded.stats.hits_taken, 4);
+        // The random state carries over, so the run continues the same way
+        assert_eq!(loaded.rng.clone().next_u64(), save.rng.clone().next_u64());
+        assert_eq!(write(&loaded).unwrap(), text);
+    }
+
+    #[test]
+    fn truncated_saves_fail_instead_of_loading_an_empty_world() {
+        let text = "(version: 1, seed: 7, rng: (seed: 7, state: 7), \
+                    player: (translation: (0, 0, 0), rotation: (0, 0, 0, 1), health: 5))";
+        assert!(matches!(parse(text), Err(SaveError::Parse(_))));
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let text = write(&sample()).unwrap().replacen("version: 1", "version: 2", 1);
+        assert!(matches!(parse(&text), Err(SaveError::UnsupportedVersion(2))));
+    }
+
+    #[test]
+    fn garbage_is_a_parse_error() {
+        assert!(matches!(parse("not a save"), Err(SaveError::Parse(_))));
+    }
+}
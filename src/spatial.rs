@@ -0,0 +1,234 @@
+use bevy::ecs::entity::{EntityHashMap, EntityHashSet};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::collision::{ObstacleBlocker, ObstacleFilter, PlacedCollider, RayHit};
+
+/// Default edge length of a grid cell in world units.
+///
+/// Roughly the size of a small prop, so most colliders touch only a few cells
+/// while the building and boundary walls still stay cheap to register.
+pub const DEFAULT_CELL_SIZE: f32 = 8.0;
+
+/// Uniform grid over the XZ plane holding every placed obstacle.
+///
+/// Each collider is registered in all cells its world bounds overlap. Queries
+/// only look at the cells they touch, so their cost grows with the obstacles
+/// near the query rather than with the size of the level. The world is flat, so
+/// the grid ignores height.
+#[derive(Resource, Debug)]
+pub struct ObstacleIndex {
+    cell_size: f32,
+    colliders: EntityHashMap<(PlacedCollider, IVec2, IVec2)>,
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl Default for ObstacleIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl ObstacleIndex {
+    pub fn new(cell_size: f32) -> Self {
+        Self { cell_size, colliders: default(), cells: default() }
+    }
+
+    /// Number of registered colliders
+    pub fn len(&self) -> usize {
+        self.colliders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colliders.is_empty()
+    }
+
+    /// Every registered collider, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &PlacedCollider> {
+        self.colliders.values().map(|(collider, _, _)| collider)
+    }
+
+    /// Adds a collider, replacing any previous one of the same entity
+    pub fn insert(&mut self, collider: PlacedCollider) {
+        self.remove(collider.entity);
+        let (min, max) = collider.aabb();
+        let (min_cell, max_cell) = (self.cell_of(min), self.cell_of(max));
+        for x in min_cell.x..=max_cell.x {
+            for z in min_cell.y..=max_cell.y {
+                self.cells.entry(IVec2::new(x, z)).or_default().push(collider.entity);
+            }
+        }
+        self.colliders.insert(collider.entity, (collider, min_cell, max_cell));
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let Some((_, min_cell, max_cell)) = self.colliders.remove(&entity) else {
+            return;
+        };
+        for x in min_cell.x..=max_cell.x {
+            for z in min_cell.y..=max_cell.y {
+                let cell = IVec2::new(x, z);
+                let Some(entities) = self.cells.get_mut(&cell) else {
+                    continue;
+                };
+                entities.retain(|&other| other != entity);
+                if entities.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    /// Colliders whose bounds come within `radius` of `center` on the XZ plane
+    pub fn colliders_near(&self, center: Vec3, radius: f32) -> Vec<PlacedCollider> {
+        let reach = Vec3::new(radius, 0.0, radius);
+        let (min_cell, max_cell) = (self.cell_of(center - reach), self.cell_of(center + reach));
+        let mut seen = EntityHashSet::default();
+        let mut found = Vec::new();
+        for x in min_cell.x..=max_cell.x {
+            for z in min_cell.y..=max_cell.y {
+                let Some(entities) = self.cells.get(&IVec2::new(x, z)) else {
+                    continue;
+                };
+                for &entity in entities {
+                    if !seen.insert(entity) {
+                        continue;
+                    }
+                    let collider = &self.colliders[&entity].0;
+                    let (min, max) = collider.aabb();
+                    let closest = center.xz().clamp(min.xz(), max.xz());
+                    if closest.distance_squared(center.xz()) <= radius * radius {
+                        found.push(*collider);
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Nearest obstacle along `dir` (normalized) within `max_distance`, skipping
+    /// `ignore`'s collider.
+    ///
+    /// Walks the cells under the ray in order and stops as soon as a hit lies
+    /// before the next cell, so long rays in open ground stay cheap.
+    pub fn cast_ray(&self, origin: Vec3, dir: Vec3, max_distance: f32, ignore: Option<Entity>) -> Option<RayHit> {
+        let mut best: Option<RayHit> = None;
+        let mut tested = EntityHashSet::default();
+        self.walk_cells(origin, dir, max_distance, |cell, cell_entry| {
+            if best.is_some_and(|hit| hit.distance <= cell_entry) {
+                return false;
+            }
+            let Some(entities) = self.cells.get(&cell) else {
+                return true;
+            };
+            for &entity in entities {
+                if Some(entity) == ignore || !tested.insert(entity) {
+                    continue;
+                }
+                let limit = best.map_or(max_distance, |hit| hit.distance);
+                if let Some((distance, normal)) = self.colliders[&entity].0.ray_cast(origin, dir, limit) {
+                    if best.is_none_or(|hit| distance < hit.distance) {
+                        best = Some(RayHit { entity, distance, normal });
+                    }
+                }
+            }
+            true
+        });
+        best
+    }
+
+    /// Whether nothing solid lies on the segment from `from` to `to`, apart from
+    /// `ignore`'s collider
+    pub fn line_of_sight(&self, from: Vec3, to: Vec3, ignore: Option<Entity>) -> bool {
+        let offset = to - from;
+        let distance = offset.length();
+        if distance <= f32::EPSILON {
+            return true;
+        }
+        self.cast_ray(from, offset / distance, distance, ignore).is_none()
+    }
+
+    fn cell_of(&self, point: Vec3) -> IVec2 {
+        (point.xz() / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Visits the cells crossed by a ray in order (Amanatides & Woo), passing the
+    /// distance at which the ray enters each one. Stops when `visit` returns false.
+    fn walk_cells(&self, origin: Vec3, dir: Vec3, max_distance: f32, mut visit: impl FnMut(IVec2, f32) -> bool) {
+        let mut cell = self.cell_of(origin);
+        let dir = dir.xz();
+        let step = IVec2::new(dir.x.signum() as i32, dir.y.signum() as i32);
+
+        // Distance to the first boundary on each axis, and between boundaries
+        let boundary = |axis: usize| {
+            if dir[axis].abs() < f32::EPSILON {
+                return (f32::INFINITY, f32::INFINITY);
+            }
+            let next = (cell[axis] + i32::from(dir[axis] > 0.0)) as f32 * self.cell_size;
+            ((next - origin.xz()[axis]) / dir[axis], self.cell_size / dir[axis].abs())
+        };
+        let (mut t_max_x, t_delta_x) = boundary(0);
+        let (mut t_max_z, t_delta_z) = boundary(1);
+
+        let mut entry = 0.0;
+        while entry <= max_distance {
+            if !visit(cell, entry) {
+                return;
+            }
+            if t_max_x < t_max_z {
+                entry = t_max_x;
+                t_max_x += t_delta_x;
+                cell.x += step.x;
+            } else {
+                entry = t_max_z;
+                t_max_z += t_delta_z;
+                cell.y += step.y;
+            }
+        }
+    }
+}
+
+/// Obstacles whose placement changed since the index last looked
+pub(crate) type MovedObstacles = (ObstacleFilter, Or<(Changed<Transform>, Changed<ObstacleBlocker>)>);
+
+/// Keeps [`ObstacleIndex`] in step with blockers being added, moved or removed
+pub(crate) fn sync_obstacle_index(
+    mut index: ResMut<ObstacleIndex>,
+    changed: Query<(Entity, &Transform, &ObstacleBlocker), MovedObstacles>,
+    mut removed: RemovedComponents<ObstacleBlocker>,
+) {
+    for entity in removed.read() {
+        index.remove(entity);
+    }
+    for (entity, transform, blocker) in changed.iter() {
+        index.insert(PlacedCollider::new(entity, transform, blocker));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_of_sight_can_skip_the_viewer_collider() {
+        let mut index = ObstacleIndex::default();
+        // A radar base resting on the ground, so its origin lies on the bottom face
+        let radar = Entity::from_raw_u32(1).unwrap();
+        let base = ObstacleBlocker { center: Vec3::Y, ..ObstacleBlocker::cuboid(Vec3::splat(1.0)) };
+        index.insert(PlacedCollider::new(radar, &Transform::IDENTITY, &base));
+
+        let player = Vec3::new(0.0, 1.0, 20.0);
+        assert!(!index.line_of_sight(Vec3::ZERO, player, None));
+        assert!(index.line_of_sight(Vec3::ZERO, player, Some(radar)));
+
+        // Other colliders still block
+        let wall = Entity::from_raw_u32(2).unwrap();
+        index.insert(PlacedCollider::new(
+            wall,
+            &Transform::from_xyz(0.0, 1.0, 10.0),
+            &ObstacleBlocker::cuboid(Vec3::new(5.0, 2.0, 0.5)),
+        ));
+        assert!(!index.line_of_sight(Vec3::ZERO, player, Some(radar)));
+        assert_eq!(index.cast_ray(Vec3::ZERO, Vec3::Z, 20.0, Some(radar)).map(|hit| hit.entity), Some(wall));
+    }
+}
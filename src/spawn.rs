@@ -0,0 +1,111 @@
+use bevy::camera::primitives::{Frustum, Sphere as BoundingSphere};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::collision::Obstacles;
+use crate::nav::NavGrid;
+use crate::{CameraController, ChasingSphere, CubeController, WorldRng, UGV_RADIUS};
+
+/// Spawn points closer than this to the player are rejected
+const MIN_PLAYER_DISTANCE: f32 = 25.0;
+/// Spawn points closer than this to another UGV are rejected
+const MIN_UGV_DISTANCE: f32 = 4.0;
+/// Radius around a spawn point that must be out of view, covering the largest UGV model
+const VIEW_CLEARANCE: f32 = 2.0;
+/// Random points tried around the player before falling back to spawn markers
+const RANDOM_ATTEMPTS: usize = 16;
+
+/// Designer-placed fallback spawn point, from the level's `spawn_points`
+#[derive(Component)]
+pub struct SpawnMarker;
+
+/// Why a spawn point was turned down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnRejection {
+    /// Off the ground
+    OffGround,
+    /// Overlapping a collider
+    InsideObstacle,
+    /// Inside the camera frustum
+    InView,
+    NearPlayer,
+    NearUgv,
+}
+
+/// Picks spawn points that are on the ground, clear of colliders, out of the
+/// player's view and away from the player and other UGVs
+#[derive(SystemParam)]
+pub struct SpawnValidator<'w, 's> {
+    obstacles: Obstacles<'w>,
+    nav: Res<'w, NavGrid>,
+    camera_query: Query<'w, 's, &'static Frustum, With<CameraController>>,
+    player_query: Query<'w, 's, &'static Transform, With<CubeController>>,
+    ugv_query: Query<'w, 's, &'static Transform, With<ChasingSphere>>,
+    marker_query: Query<'w, 's, &'static Transform, With<SpawnMarker>>,
+}
+
+impl SpawnValidator<'_, '_> {
+    /// Checks one candidate point on the ground
+    pub fn check(&self, point: Vec3) -> Result<(), SpawnRejection> {
+        if !self.nav.contains(point) {
+            return Err(SpawnRejection::OffGround);
+        }
+        if self.obstacles.overlaps(point + Vec3::Y * UGV_RADIUS, UGV_RADIUS) {
+            return Err(SpawnRejection::InsideObstacle);
+        }
+        if let Ok(player) = self.player_query.single() {
+            if player.translation.xz().distance(point.xz()) < MIN_PLAYER_DISTANCE {
+                return Err(SpawnRejection::NearPlayer);
+            }
+        }
+        if self.ugv_query.iter().any(|ugv| ugv.translation.xz().distance(point.xz()) < MIN_UGV_DISTANCE) {
+            return Err(SpawnRejection::NearUgv);
+        }
+        let bounds = BoundingSphere { center: point.into(), radius: VIEW_CLEARANCE };
+        if self.camera_query.iter().any(|frustum| frustum.intersects_sphere(&bounds, true)) {
+            return Err(SpawnRejection::InView);
+        }
+        Ok(())
+    }
+
+    /// Finds a valid point `min_radius..max_radius` from the player, falling back
+    /// to a random valid spawn marker. Logs and returns `None` if neither works.
+    pub fn find(&self, min_radius: f32, max_radius: f32, rng: &mut WorldRng) -> Option<Vec3> {
+        let center = self.player_query.single().map_or(Vec3::ZERO, |player| player.translation);
+        let mut rejections = Vec::with_capacity(RANDOM_ATTEMPTS);
+        for _ in 0..RANDOM_ATTEMPTS {
+            let angle = rng.angle();
+            let radius = rng.range(min_radius, max_radius);
+            let point = Vec3::new(center.x + angle.cos() * radius, 0.0, center.z + angle.sin() * radius);
+            match self.check(point) {
+                Ok(()) => return Some(point),
+                Err(rejection) => rejections.push(rejection),
+            }
+        }
+
+        let markers: Vec<Vec3> = self
+            .marker_query
+            .iter()
+            .map(|marker| marker.translation.with_y(0.0))
+            .filter(|point| self.check(*point).is_ok())
+            .collect();
+        if markers.is_empty() {
+            warn!(
+                "No valid spawn point around {:?} or at any of {} spawn markers; random points were rejected as {:?}",
+                center,
+                self.marker_query.iter().len(),
+                rejections
+            );
+            return None;
+        }
+        debug!("No valid random spawn point ({:?}), using a spawn marker", rejections);
+        Some(markers[(rng.next_u64() % markers.len() as u64) as usize])
+    }
+}
+
+/// Draws each spawn marker as a small magenta sphere
+pub(crate) fn draw_spawn_markers(mut gizmos: Gizmos, marker_query: Query<&Transform, With<SpawnMarker>>) {
+    for transform in marker_query.iter() {
+        gizmos.sphere(Isometry3d::from_translation(transform.translation), 0.5, Color::srgb(1.0, 0.0, 1.0));
+    }
+}
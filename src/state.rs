@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::damage::{Defense, Died};
+use crate::level::{CurrentLevel, LevelDescription};
+use crate::radar::Radar;
+use crate::waves::WaveDirector;
+use crate::{ChasingSphere, CubeController, Footstep, Health, SmokeParticle, WorldRng};
+
+/// Top-level flow of a session. Gameplay systems only run in `Playing`.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    /// Waiting for the level file and spawning the world
+    #[default]
+    Loading,
+    MainMenu,
+    Playing,
+    Paused,
+    /// The player's `Health` reached zero
+    GameOver,
+}
+
+/// Results of the current run, shown on the game over screen
+#[derive(Resource, Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RunStats {
+    /// Seconds spent in `Playing`
+    pub survival_time: f32,
+    /// Hits that took health off the player
+    pub hits_taken: u32,
+}
+
+// HUD blue, matching the web health bar
+pub(crate) const HUD_COLOR: Color = Color::srgb(0.0, 0.4, 0.8);
+
+/// Leaves `Loading` once the level has been spawned
+pub(crate) fn finish_loading(level: Res<CurrentLevel>, mut next_state: ResMut<NextState<GameState>>) {
+    if level.spawned {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+pub(crate) fn spawn_main_menu(mut commands: Commands) {
+    commands.spawn((
+        DespawnOnExit(GameState::MainMenu),
+        overlay_root(),
+        children![
+            overlay_text("LEVEL 0", 48.0),
+            overlay_text("Press Enter to start", 18.0),
+            overlay_text("F9 loads the saved game", 18.0),
+        ],
+    ));
+}
+
+pub(crate) fn start_game(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Playing);
+}
+
+pub(crate) fn tick_run_stats(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.survival_time += time.delta_secs();
+}
+
+/// Ends the run when the player dies
+pub(crate) fn check_game_over(
+    mut died: MessageReader<Died>,
+    player_query: Query<(), With<CubeController>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if died.read().any(|death| player_query.contains(death.entity)) {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+pub(crate) fn spawn_game_over_screen(
+    mut commands: Commands,
+    stats: Res<RunStats>,
+    director_query: Query<&WaveDirector>,
+) {
+    let wave = director_query.iter().map(WaveDirector::number).max().unwrap_or(0);
+    commands.spawn((
+        DespawnOnExit(GameState::GameOver),
+        overlay_root(),
+        children![
+            overlay_text("GAME OVER", 48.0),
+            overlay_text(format!("Survived {:.1} s", stats.survival_time), 18.0),
+            overlay_text(format!("Reached wave {wave}"), 18.0),
+            overlay_text(format!("Hits taken: {}", stats.hits_taken), 18.0),
+            overlay_text("Press R to restart", 18.0),
+        ],
+    ));
+}
+
+/// Random state right after the level was spawned, so every run of a seed
+/// replays the same waves
+#[derive(Resource, Clone, Debug)]
+pub struct RunStartRng(pub WorldRng);
+
+/// Everything a run leaves lying around that a restart clears
+type RunLeftovers = Or<(With<ChasingSphere>, With<SmokeParticle>, With<Footstep>)>;
+
+/// Player parts a restart puts back to their starting values
+type PlayerResetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Transform, &'static mut Health, &'static mut Visibility, Option<&'static mut Defense>),
+    With<CubeController>,
+>;
+
+/// Starts a fresh run in the world that is already spawned.
+///
+/// Clears UGVs, smoke and footprints, puts the player back at the level's start
+/// with full health and no invulnerability left, rearms the radars, restarts the
+/// waves and replays the random stream.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn restart(
+    mut commands: Commands,
+    leftovers: Query<Entity, RunLeftovers>,
+    mut player_query: PlayerResetQuery,
+    mut radar_query: Query<&mut Radar>,
+    mut director_query: Query<&mut WaveDirector>,
+    level: Res<CurrentLevel>,
+    levels: Res<Assets<LevelDescription>>,
+    run_start: Res<RunStartRng>,
+    mut rng: ResMut<WorldRng>,
+    mut stats: ResMut<RunStats>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for entity in leftovers.iter() {
+        commands.entity(entity).despawn();
+    }
+    let start = levels.get(&level.handle).map_or(Vec3::ZERO, |desc| Vec3::from_array(desc.player.position));
+    for (mut transform, mut health, mut visibility, defense) in player_query.iter_mut() {
+        *transform = Transform::from_translation(start);
+        health.current = health.max;
+        *visibility = Visibility::Inherited;
+        if let Some(mut defense) = defense {
+            defense.reset();
+        }
+    }
+    // `update_health` pushes the restored value to the HUD; the web page also
+    // gets to reset its own bar
+    #[cfg(target_arch = "wasm32")]
+    crate::call_page("resetHealth", None);
+
+    for mut radar in radar_query.iter_mut() {
+        radar.reset();
+    }
+    for mut director in director_query.iter_mut() {
+        director.reset();
+    }
+    *rng = run_start.0.clone();
+    *stats = RunStats::default();
+    next_state.set(GameState::Playing);
+}
+
+pub(crate) fn overlay_root() -> impl Bundle {
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+    )
+}
+
+pub(crate) fn overlay_text(text: impl Into<String>, font_size: f32) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont { font_size, ..default() },
+        TextColor(HUD_COLOR),
+    )
+}
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::collision::Obstacles;
+use crate::WorldRng;
+
+/// Per-UGV steering parameters.
+///
+/// Randomized at spawn so a group of UGVs spreads out: each one keeps its own
+/// distance from the others and approaches the player from its own side.
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Steering {
+    /// Distance to the final target at which the UGV starts slowing down
+    pub arrive_radius: f32,
+    /// Other UGVs closer than this push the UGV away
+    pub separation_radius: f32,
+    /// Strength of the push from other UGVs, relative to full speed
+    pub separation_weight: f32,
+    /// How far ahead to look for obstacles
+    pub avoidance_distance: f32,
+    /// Strength of the turn away from obstacles ahead, relative to full speed
+    pub avoidance_weight: f32,
+    /// Angle in radians by which the approach to a chased target is swung sideways
+    pub flank_angle: f32,
+    /// Furthest the approach point strays from the chased target
+    pub flank_radius: f32,
+}
+
+impl Steering {
+    pub fn random(rng: &mut WorldRng) -> Self {
+        Self {
+            arrive_radius: 2.0,
+            separation_radius: rng.range(2.5, 3.5),
+            separation_weight: 1.5,
+            avoidance_distance: 4.0,
+            avoidance_weight: 1.0,
+            flank_angle: rng.range(-1.0, 1.0),
+            flank_radius: rng.range(6.0, 12.0),
+        }
+    }
+}
+
+/// Full-speed velocity towards `target` on the ground plane
+pub fn seek(position: Vec3, target: Vec3, max_speed: f32) -> Vec3 {
+    (target - position).with_y(0.0).normalize_or_zero() * max_speed
+}
+
+/// Like [`seek`], but slowing down linearly inside `slow_radius`
+pub fn arrive(position: Vec3, target: Vec3, max_speed: f32, slow_radius: f32) -> Vec3 {
+    let offset = (target - position).with_y(0.0);
+    let distance = offset.length();
+    if distance <= f32::EPSILON {
+        return Vec3::ZERO;
+    }
+    offset / distance * max_speed * (distance / slow_radius).min(1.0)
+}
+
+/// Push away from every neighbor within `radius`, stronger the closer it is.
+/// Roughly unit length when a single neighbor is touching.
+pub fn separation(position: Vec3, neighbors: impl IntoIterator<Item = Vec3>, radius: f32) -> Vec3 {
+    neighbors
+        .into_iter()
+        .filter_map(|neighbor| {
+            let away = (position - neighbor).with_y(0.0);
+            let distance = away.length();
+            (distance > f32::EPSILON && distance < radius).then(|| away / distance * (1.0 - distance / radius))
+        })
+        .sum()
+}
+
+/// Sideways push away from the first obstacle within `lookahead` along `velocity`.
+/// Unit length when about to touch it, fading to zero at `lookahead`.
+pub fn avoid_obstacles(position: Vec3, velocity: Vec3, obstacles: &Obstacles, lookahead: f32) -> Vec3 {
+    let Some(direction) = velocity.with_y(0.0).try_normalize() else {
+        return Vec3::ZERO;
+    };
+    let Some(hit) = obstacles.cast_ray(position, direction, lookahead, None) else {
+        return Vec3::ZERO;
+    };
+    // Keep only the part of the normal across the direction of travel
+    let normal = hit.normal.with_y(0.0);
+    let across = (normal - direction * normal.dot(direction)).normalize_or_zero();
+    across * (1.0 - hit.distance / lookahead)
+}
+
+/// Point to drive at when chasing `target` from the side given by `angle`.
+///
+/// The approach swings by `angle` around the target and straightens out as the
+/// UGV closes in, so chasers coming from one direction fan out around the target.
+pub fn flank_point(position: Vec3, target: Vec3, angle: f32, radius: f32) -> Vec3 {
+    let offset = (position - target).with_y(0.0);
+    let swung = Quat::from_rotation_y(angle) * offset.normalize_or_zero();
+    target + swung * (offset.length() * 0.5).min(radius)
+}
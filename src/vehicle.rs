@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Height of the ground surface UGVs drive on
+pub const GROUND_HEIGHT: f32 = 0.0;
+
+/// Ground vehicle kinematics of a UGV.
+///
+/// The vehicle always drives along its own nose (local +Z) and can only turn at
+/// `turn_rate`, so it arcs towards where steering wants to go instead of
+/// snapping. It slows down for sharp turns and turns on the spot when the target
+/// is behind it.
+#[derive(Component, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Vehicle {
+    /// Top speed in units per second
+    pub max_speed: f32,
+    /// Speed gained per second; braking is twice as strong
+    pub acceleration: f32,
+    /// Yaw rate in radians per second
+    pub turn_rate: f32,
+    /// Current forward speed
+    pub speed: f32,
+}
+
+impl Vehicle {
+    pub fn new(max_speed: f32, acceleration: f32, turn_rate: f32) -> Self {
+        Self { max_speed, acceleration, turn_rate, speed: 0.0 }
+    }
+
+    /// Advances the vehicle by `dt` towards `desired_velocity` on the ground plane.
+    ///
+    /// With no desired velocity the vehicle brakes and, if given, turns towards
+    /// `face`. Returns the new rotation and the velocity to move with.
+    pub fn drive(&mut self, rotation: Quat, desired_velocity: Vec3, face: Option<Vec3>, dt: f32) -> (Quat, Vec3) {
+        let forward = heading_of(rotation);
+        let desired = desired_velocity.with_y(0.0);
+        let wanted_heading = desired.try_normalize().or_else(|| face.and_then(|face| face.with_y(0.0).try_normalize()));
+
+        let mut yaw = forward.x.atan2(forward.z);
+        let mut alignment = 1.0;
+        if let Some(wanted) = wanted_heading {
+            let error = wrap_angle(wanted.x.atan2(wanted.z) - yaw);
+            let max_turn = self.turn_rate * dt;
+            yaw += error.clamp(-max_turn, max_turn);
+            alignment = error.cos().max(0.0);
+        }
+
+        let target_speed = desired.length().min(self.max_speed) * alignment;
+        let rate = if target_speed < self.speed { self.acceleration * 2.0 } else { self.acceleration };
+        self.speed += (target_speed - self.speed).clamp(-rate * dt, rate * dt);
+
+        let rotation = Quat::from_rotation_y(yaw);
+        (rotation, heading_of(rotation) * self.speed)
+    }
+}
+
+/// Direction a vehicle with `rotation` drives in, flattened onto the ground
+fn heading_of(rotation: Quat) -> Vec3 {
+    (rotation * Vec3::Z).with_y(0.0).try_normalize().unwrap_or(Vec3::Z)
+}
+
+/// Wraps an angle into `(-π, π]`
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = angle.rem_euclid(std::f32::consts::TAU);
+    if wrapped > std::f32::consts::PI {
+        wrapped - std::f32::consts::TAU
+    } else {
+        wrapped
+    }
+}
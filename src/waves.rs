@@ -0,0 +1,357 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::archetype::SpawnChoice;
+use crate::state::HUD_COLOR;
+
+/// One wave of the level's schedule, with its archetypes resolved
+#[derive(Debug, Clone)]
+pub struct Wave {
+    /// Enemies spawned at intensity 1
+    pub count: usize,
+    /// Archetypes to pick from, by weight
+    pub choices: Vec<SpawnChoice>,
+    /// Seconds between spawns at intensity 1
+    pub spawn_interval: f32,
+    /// Seconds of quiet after the last spawn at intensity 1
+    pub break_time: f32,
+    /// Spawn ring around the player
+    pub min_radius: f32,
+    pub max_radius: f32,
+}
+
+/// How hard the waves get.
+///
+/// Intensity starts at 1, grows linearly with survival time up to
+/// `max_intensity`, and is eased off while the player is low on health. Higher
+/// intensity means bigger waves, faster spawns and shorter breaks.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct DifficultyCurve {
+    /// Seconds of survival for the intensity to go up by 1
+    pub ramp_time: f32,
+    pub max_intensity: f32,
+    /// Health fraction below which the pressure is eased
+    pub low_health: f32,
+    /// Intensity multiplier at zero health, rising back to 1 at `low_health`
+    pub low_health_relief: f32,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        Self { ramp_time: 180.0, max_intensity: 3.0, low_health: 0.3, low_health_relief: 0.5 }
+    }
+}
+
+impl DifficultyCurve {
+    pub fn intensity(&self, survival_time: f32, health_fraction: f32) -> f32 {
+        let ramp = (1.0 + survival_time / self.ramp_time).min(self.max_intensity);
+        let relief = if health_fraction < self.low_health {
+            self.low_health_relief.lerp(1.0, (health_fraction / self.low_health).max(0.0))
+        } else {
+            1.0
+        };
+        ramp * relief
+    }
+}
+
+/// What the director is doing between two spawns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WavePhase {
+    /// Waiting for the next wave
+    Break,
+    /// Spawning the current wave, with this many enemies still to come
+    Spawning { remaining: usize },
+}
+
+/// Runs the level's waves: spawns each one over time, then takes a break
+/// before the next. Once the schedule runs out its last wave repeats, growing
+/// with the difficulty curve.
+#[derive(Component, Debug)]
+pub struct WaveDirector {
+    waves: Vec<Wave>,
+    pub curve: DifficultyCurve,
+    /// Spawning pauses while this many enemies are alive
+    pub max_alive: usize,
+    /// Seconds before the first wave
+    first_break: f32,
+    /// Current wave, counting from 1; 0 before the first wave
+    number: u32,
+    phase: WavePhase,
+    /// Seconds until the next spawn, or until the break ends
+    time_left: f32,
+    /// Seconds left of a radar alarm, which hurries the director along
+    pub(crate) alarm_time_left: f32,
+}
+
+impl WaveDirector {
+    pub fn new(waves: Vec<Wave>, curve: DifficultyCurve, max_alive: usize, first_break: f32) -> Self {
+        Self {
+            waves,
+            curve,
+            max_alive,
+            first_break,
+            number: 0,
+            phase: WavePhase::Break,
+            time_left: first_break,
+            alarm_time_left: 0.0,
+        }
+    }
+
+    /// Starts over from the break before the first wave
+    pub fn reset(&mut self) {
+        self.number = 0;
+        self.phase = WavePhase::Break;
+        self.time_left = self.first_break;
+        self.alarm_time_left = 0.0;
+    }
+
+    /// Progress through the schedule, for a save game
+    pub fn save(&self) -> DirectorState {
+        DirectorState {
+            number: self.number,
+            phase: self.phase,
+            time_left: self.time_left,
+            alarm_time_left: self.alarm_time_left,
+        }
+    }
+
+    /// Picks up the schedule where a saved director left off
+    pub fn restore(&mut self, state: &DirectorState) {
+        self.number = state.number;
+        self.phase = state.phase;
+        self.time_left = state.time_left;
+        self.alarm_time_left = state.alarm_time_left;
+    }
+
+    pub fn number(&self) -> u32 {
+        self.number
+    }
+
+    pub fn phase(&self) -> WavePhase {
+        self.phase
+    }
+
+    /// Seconds until the next spawn, or until the break ends
+    pub fn time_left(&self) -> f32 {
+        self.time_left
+    }
+
+    /// The wave being spawned, or the last one during a break
+    pub fn current_wave(&self) -> Option<&Wave> {
+        let index = (self.number as usize).checked_sub(1)?;
+        self.waves.get(index.min(self.waves.len().saturating_sub(1)))
+    }
+
+    /// Advances by `dt` seconds at `intensity` with `alive` enemies in the world.
+    /// Returns the wave to spawn one enemy of when a spawn is due.
+    pub fn tick(&mut self, dt: f32, intensity: f32, alive: usize) -> Option<&Wave> {
+        self.time_left -= dt;
+        if self.time_left > 0.0 || self.waves.is_empty() {
+            return None;
+        }
+
+        match self.phase {
+            WavePhase::Break => {
+                self.number += 1;
+                let count = self.current_wave()?.count;
+                let remaining = ((count as f32 * intensity).round() as usize).max(1);
+                info!("Wave {} starting: {} enemies at intensity {:.2}", self.number, remaining, intensity);
+                self.phase = WavePhase::Spawning { remaining };
+                self.time_left = 0.0;
+                None
+            }
+            // Hold the spawn until there is room
+            WavePhase::Spawning { .. } if alive >= self.max_alive => {
+                self.time_left = 0.0;
+                None
+            }
+            WavePhase::Spawning { remaining } => {
+                let wave = self.current_wave()?;
+                let (spawn_interval, break_time) = (wave.spawn_interval, wave.break_time);
+                if remaining > 1 {
+                    self.phase = WavePhase::Spawning { remaining: remaining - 1 };
+                    self.time_left = spawn_interval / intensity;
+                } else {
+                    self.phase = WavePhase::Break;
+                    self.time_left = break_time / intensity;
+                }
+                self.current_wave()
+            }
+        }
+    }
+
+    /// Puts back a spawn that found no place to go, to retry after `delay` seconds
+    pub fn requeue(&mut self, delay: f32) {
+        let remaining = match self.phase {
+            WavePhase::Spawning { remaining } => remaining + 1,
+            WavePhase::Break => 1,
+        };
+        self.phase = WavePhase::Spawning { remaining };
+        self.time_left = delay;
+    }
+}
+
+/// Saved progress of a [`WaveDirector`]; the schedule itself comes from the level
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DirectorState {
+    pub number: u32,
+    pub phase: WavePhase,
+    pub time_left: f32,
+    pub alarm_time_left: f32,
+}
+
+/// HUD text showing the wave number
+#[derive(Component)]
+pub(crate) struct WaveLabel;
+
+pub(crate) fn wave_label() -> impl Bundle {
+    (
+        WaveLabel,
+        Text::new(""),
+        TextFont { font_size: 18.0, ..default() },
+        TextColor(HUD_COLOR),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.0),
+            right: Val::Px(20.0),
+            ..default()
+        },
+    )
+}
+
+pub(crate) fn update_wave_label(
+    director_query: Query<&WaveDirector>,
+    mut label_query: Query<&mut Text, With<WaveLabel>>,
+) {
+    let Ok(director) = director_query.single() else {
+        return;
+    };
+    let countdown = director.time_left().max(0.0).ceil();
+    let label = match (director.phase(), director.number()) {
+        (WavePhase::Break, 0) => format!("First wave in {countdown} s"),
+        (WavePhase::Break, number) => format!("Wave {number} - next in {countdown} s"),
+        (WavePhase::Spawning { .. }, number) => format!("Wave {number}"),
+    };
+    for mut text in label_query.iter_mut() {
+        // Only touch the text when it changes, to avoid relayouts every frame
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wave(count: usize) -> Wave {
+        Wave { count, choices: Vec::new(), spawn_interval: 2.0, break_time: 10.0, min_radius: 30.0, max_radius: 60.0 }
+    }
+
+    fn director(waves: Vec<Wave>, max_alive: usize) -> WaveDirector {
+        WaveDirector::new(waves, DifficultyCurve::default(), max_alive, 5.0)
+    }
+
+    fn spawns(director: &mut WaveDirector, dt: f32, intensity: f32, alive: usize) -> bool {
+        director.tick(dt, intensity, alive).is_some()
+    }
+
+    #[test]
+    fn first_break_leads_into_spawning() {
+        let mut director = director(vec![wave(3)], 10);
+        assert!(!spawns(&mut director, 4.9, 1.0, 0));
+        assert_eq!((director.number(), director.phase()), (0, WavePhase::Break));
+
+        assert!(!spawns(&mut director, 0.2, 1.0, 0));
+        assert_eq!((director.number(), director.phase()), (1, WavePhase::Spawning { remaining: 3 }));
+
+        // The first enemy comes right away, the rest one interval apart
+        assert!(spawns(&mut director, 0.0, 1.0, 0));
+        assert_eq!(director.phase(), WavePhase::Spawning { remaining: 2 });
+        assert!(!spawns(&mut director, 1.5, 1.0, 1));
+        assert!(spawns(&mut director, 0.5, 1.0, 1));
+        assert!(spawns(&mut director, 2.0, 1.0, 2));
+        assert_eq!(director.phase(), WavePhase::Break);
+        assert_eq!(director.time_left(), 10.0);
+    }
+
+    #[test]
+    fn spawning_holds_while_max_alive_are_around() {
+        let mut director = director(vec![wave(3)], 2);
+        spawns(&mut director, 5.0, 1.0, 0);
+        assert!(spawns(&mut director, 0.0, 1.0, 0));
+
+        for _ in 0..10 {
+            assert!(!spawns(&mut director, 5.0, 1.0, 2));
+        }
+        assert_eq!(director.phase(), WavePhase::Spawning { remaining: 2 });
+        // Room frees up and the held spawn goes out at once
+        assert!(spawns(&mut director, 0.0, 1.0, 1));
+        assert_eq!(director.phase(), WavePhase::Spawning { remaining: 1 });
+    }
+
+    #[test]
+    fn last_wave_repeats_and_grows_with_intensity() {
+        let mut director = director(vec![wave(1), wave(2)], 100);
+        spawns(&mut director, 5.0, 1.0, 0);
+        assert!(spawns(&mut director, 0.0, 1.0, 0));
+        spawns(&mut director, 10.0, 1.0, 0);
+        assert_eq!((director.number(), director.phase()), (2, WavePhase::Spawning { remaining: 2 }));
+        assert!(spawns(&mut director, 0.0, 1.0, 0));
+        assert!(spawns(&mut director, 2.0, 1.0, 0));
+
+        // Past the schedule: the last wave again, doubled, with spawns and break halved
+        spawns(&mut director, 10.0, 2.0, 0);
+        assert_eq!((director.number(), director.phase()), (3, WavePhase::Spawning { remaining: 4 }));
+        assert_eq!(director.current_wave().map(|wave| wave.count), Some(2));
+        assert!(spawns(&mut director, 0.0, 2.0, 0));
+        assert_eq!(director.time_left(), 1.0);
+        for _ in 0..3 {
+            assert!(spawns(&mut director, 1.0, 2.0, 0));
+        }
+        assert_eq!(director.phase(), WavePhase::Break);
+        assert_eq!(director.time_left(), 5.0);
+    }
+
+    #[test]
+    fn requeue_puts_a_rejected_spawn_back() {
+        let mut director = director(vec![wave(2)], 10);
+        spawns(&mut director, 5.0, 1.0, 0);
+        assert!(spawns(&mut director, 0.0, 1.0, 0));
+        director.requeue(1.0);
+        assert_eq!(director.phase(), WavePhase::Spawning { remaining: 2 });
+        assert_eq!(director.time_left(), 1.0);
+
+        // The last spawn of a wave is put back even though the break had begun
+        assert!(spawns(&mut director, 1.0, 1.0, 0));
+        assert!(spawns(&mut director, 2.0, 1.0, 0));
+        assert_eq!(director.phase(), WavePhase::Break);
+        director.requeue(1.0);
+        assert_eq!(director.phase(), WavePhase::Spawning { remaining: 1 });
+        assert!(!spawns(&mut director, 0.5, 1.0, 0));
+        assert!(spawns(&mut director, 0.5, 1.0, 0));
+        assert_eq!((director.number(), director.phase()), (1, WavePhase::Break));
+    }
+
+    #[test]
+    fn empty_schedule_never_spawns() {
+        let mut director = director(Vec::new(), 10);
+        for _ in 0..10 {
+            assert!(!spawns(&mut director, 5.0, 1.0, 0));
+        }
+        assert_eq!(director.number(), 0);
+    }
+
+    #[test]
+    fn intensity_ramps_up_and_eases_off_at_low_health() {
+        let curve = DifficultyCurve::default();
+        assert_eq!(curve.intensity(0.0, 1.0), 1.0);
+        assert_eq!(curve.intensity(180.0, 1.0), 2.0);
+        assert_eq!(curve.intensity(10_000.0, 1.0), 3.0);
+
+        assert_eq!(curve.intensity(0.0, 0.3), 1.0);
+        assert_eq!(curve.intensity(0.0, 0.15), 0.75);
+        assert_eq!(curve.intensity(0.0, 0.0), 0.5);
+        assert_eq!(curve.intensity(180.0, 0.0), 1.0);
+    }
+}